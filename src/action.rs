@@ -1,7 +1,13 @@
+use crossterm::event::KeyEvent;
 use serde::{Deserialize, Serialize};
-use strum::Display;
+use strum::{Display, VariantNames};
+
+use crate::{
+  app::Mode, editor::FileLocation, layout::LayoutPreset, ports::ListeningPort, search::ProjectSort,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase")]
 pub enum ProjectScript {
   Start,
   Build,
@@ -14,16 +20,7 @@ pub enum ProjectCommand {
   StopAll,
 }
 
-impl ProjectScript {
-  pub fn to_string(&self) -> String {
-    match self {
-      ProjectScript::Start => "start".to_string(),
-      ProjectScript::Build => "build".to_string(),
-    }
-  }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, VariantNames, Serialize, Deserialize)]
 pub enum Action {
   Tick,
   Render,
@@ -34,6 +31,63 @@ pub enum Action {
   ClearScreen,
   Error(String),
   Help,
+  EnterMode(Mode),
+  PendingKeys(Vec<KeyEvent>),
+  SetTheme(String),
+  ReloadConfig,
+  Notify(String),
+  CommandPalette,
+  Doctor,
   ProjectScript(ProjectScript),
   ProjectCommand(ProjectCommand),
+  /// Runs a package.json script of the named project.
+  RunScript(String, String),
+  StopProject(String),
+  /// Stops the selected project if it runs and starts its last script again.
+  Restart,
+  RestartProject(String),
+  /// A process the sampler tracked exited.
+  ProcessExited(u32),
+  /// The sampler took a new snapshot of the running processes.
+  ProcessesSampled,
+  /// Replaces the project search query.
+  Filter(String),
+  SortProjects(ProjectSort),
+  /// Sets a `:set` option, or toggles it when the value is `None`.
+  SetOption(String, Option<bool>),
+  SetLayout(LayoutPreset),
+  /// Maximizes the focused panel, or restores the layout.
+  ToggleZoom,
+  /// Jumps to the top of the focused panel.
+  ScrollTop,
+  /// Jumps to the end of the focused panel, following new output.
+  ScrollBottom,
+  /// Shows or hides the merged log of all projects.
+  ToggleTimeline,
+  /// Shows the merged log of the named projects, or of all projects if empty.
+  TimelineProjects(Vec<String>),
+  /// Shows only merged log lines containing the text.
+  TimelineFilter(String),
+  /// Shows or hides the problems parsed from the output of all projects.
+  ToggleProblems,
+  /// Shows or hides the listening TCP ports of the machine.
+  TogglePorts,
+  /// The listening ports found by a scan in the background.
+  PortsListed(Vec<ListeningPort>),
+  /// Terminates the process listening on the port.
+  KillPort(u16),
+  /// Opens `http://localhost:<port>` in the browser.
+  OpenPort(u16),
+  /// Copies `http://localhost:<port>` to the clipboard.
+  CopyPort(u16),
+  /// Forwards keys to the script of the focused output, which runs in a terminal.
+  Interact,
+  /// Shows or hides the shell tabs, opening a shell in the selected project if there are none.
+  ToggleShell,
+  /// Opens a shell tab in the directory of the named project, or of the selected one.
+  OpenShell(Option<String>),
+  /// Opens a file in the editor, suspending the TUI for terminal editors.
+  OpenFile(FileLocation),
+  /// Runs a `:` command line, e.g. `"<Ctrl-r>": { "Command": "run web-app test" }`.
+  Command(String),
 }
@@ -1,14 +1,20 @@
+use std::time::{Duration, Instant};
+
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
+use notify::RecommendedWatcher;
 use ratatui::prelude::Rect;
 use serde::{Deserialize, Serialize};
+use strum::VariantNames;
 use tokio::sync::mpsc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
   action::Action,
-  components::{fps::FpsCounter, home::Home, Component},
-  config::Config,
+  components::{fps::FpsCounter, home::Home, toast::Toast, which_key::WhichKey, Component},
+  config::{self, Config},
+  doctor::{self, Diagnostic, Severity},
+  editor::{self, EditorCommand, FileLocation},
   tui::{Event, Tui},
 };
 
@@ -20,34 +26,66 @@ pub struct App {
   should_quit: bool,
   should_suspend: bool,
   mode: Mode,
-  last_tick_key_events: Vec<KeyEvent>,
+  pending_keys: Vec<KeyEvent>,
+  last_key_at: Instant,
+  reload_config_at: Option<Instant>,
+  _config_watcher: Option<RecommendedWatcher>,
   action_tx: mpsc::UnboundedSender<Action>,
   action_rx: mpsc::UnboundedReceiver<Action>,
 }
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+  Default, Debug, Copy, Clone, PartialEq, Eq, Hash, VariantNames, Serialize, Deserialize,
+)]
 pub enum Mode {
   #[default]
   Home,
+  /// A component is capturing text input, so only bindings configured for this mode apply.
+  Input,
+  /// Keys are forwarded to a script running in a terminal, until the `interact_escape` key.
+  Interact,
 }
 
 impl App {
   pub fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
     let (action_tx, action_rx) = mpsc::unbounded_channel();
+    let config_watcher = config::watch(action_tx.clone())
+      .inspect_err(|err| warn!("Not watching the config directory: {err}"))
+      .ok();
     Ok(Self {
       tick_rate,
       frame_rate,
-      components: vec![Box::new(Home::default()), Box::new(FpsCounter::default())],
+      components: vec![
+        Box::new(Home::default()),
+        Box::new(FpsCounter::default()),
+        Box::new(WhichKey::default()),
+        Box::new(Toast::default()),
+      ],
       should_quit: false,
       should_suspend: false,
       config: Config::new()?,
       mode: Mode::Home,
-      last_tick_key_events: Vec::new(),
+      pending_keys: Vec::new(),
+      last_key_at: Instant::now(),
+      reload_config_at: None,
+      _config_watcher: config_watcher,
       action_tx,
       action_rx,
     })
   }
 
+  /// Shows problems found at startup once the UI is running.
+  pub fn report(&self, diagnostics: &[Diagnostic]) -> Result<()> {
+    report(&self.action_tx, diagnostics)
+  }
+
+  /// Runs the slower workspace checks on a blocking task, so the first frame does not wait for
+  /// them, and shows their problems as they come.
+  pub fn check_workspace(&self) {
+    let action_tx = self.action_tx.clone();
+    tokio::task::spawn_blocking(move || report(&action_tx, &doctor::diagnose_workspace()));
+  }
+
   pub async fn run(&mut self) -> Result<()> {
     let mut tui = Tui::new()?
       .mouse(true) // uncomment this line to enable mouse support
@@ -85,7 +123,18 @@ impl App {
   }
 
   async fn handle_events(&mut self, tui: &mut Tui) -> Result<()> {
-    let Some(event) = tui.next_event().await else {
+    let event = if self.pending_keys.is_empty() {
+      tui.next_event().await
+    } else {
+      // Wake up when the chord times out rather than at the next tick.
+      let timeout = Duration::from_millis(self.config.config.chord_timeout);
+      let deadline = tokio::time::Instant::from_std(self.last_key_at + timeout);
+      tokio::select! {
+        event = tui.next_event() => event,
+        _ = tokio::time::sleep_until(deadline) => return self.expire_pending_keys(),
+      }
+    };
+    let Some(event) = event else {
       return Ok(());
     };
     let action_tx = self.action_tx.clone();
@@ -110,22 +159,38 @@ impl App {
     let Some(keymap) = self.config.keybindings.get(&self.mode) else {
       return Ok(());
     };
-    match keymap.get(&vec![key]) {
-      Some(action) => {
-        info!("Got action: {action:?}");
-        action_tx.send(action.clone())?;
-      }
-      _ => {
-        // If the key was not handled as a single key action,
-        // then consider it for multi-key combinations.
-        self.last_tick_key_events.push(key);
-
-        // Check for multi-key combinations
-        if let Some(action) = keymap.get(&self.last_tick_key_events) {
-          info!("Got action: {action:?}");
-          action_tx.send(action.clone())?;
-        }
-      }
+    let is_prefix = |keys: &[KeyEvent]| {
+      keymap
+        .keys()
+        .any(|sequence| sequence.len() > keys.len() && sequence.starts_with(keys))
+    };
+
+    let had_pending_keys = !self.pending_keys.is_empty();
+    self.pending_keys.push(key);
+    self.last_key_at = Instant::now();
+    if !is_prefix(&self.pending_keys) && !keymap.contains_key(&self.pending_keys) {
+      // The key does not continue the pending chord, so consider it on its own.
+      self.pending_keys = vec![key];
+    }
+
+    if let Some(action) = keymap.get(&self.pending_keys) {
+      info!("Got action: {action:?}");
+      action_tx.send(action.clone())?;
+      self.pending_keys.clear();
+    } else if !is_prefix(&self.pending_keys) {
+      self.pending_keys.clear();
+    }
+    if had_pending_keys || !self.pending_keys.is_empty() {
+      action_tx.send(Action::PendingKeys(self.pending_keys.clone()))?;
+    }
+    Ok(())
+  }
+
+  fn expire_pending_keys(&mut self) -> Result<()> {
+    let timeout = Duration::from_millis(self.config.config.chord_timeout);
+    if !self.pending_keys.is_empty() && self.last_key_at.elapsed() >= timeout {
+      self.pending_keys.clear();
+      self.action_tx.send(Action::PendingKeys(Vec::new()))?;
     }
     Ok(())
   }
@@ -136,12 +201,17 @@ impl App {
         debug!("{action:?}");
       }
       match action {
-        Action::Tick => {
-          self.last_tick_key_events.drain(..);
+        Action::Tick => self.reload_config_if_due()?,
+        // Editors touch a file several times per save, so wait for the changes to settle.
+        Action::ReloadConfig => {
+          self.reload_config_at = Some(Instant::now() + Duration::from_millis(200))
         }
         Action::Quit => self.should_quit = true,
         Action::Suspend => self.should_suspend = true,
         Action::Resume => self.should_suspend = false,
+        Action::EnterMode(mode) => self.mode = mode,
+        Action::Doctor => self.run_doctor()?,
+        Action::OpenFile(ref location) => self.open_file(tui, location)?,
         Action::ClearScreen => tui.terminal.clear()?,
         Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
         Action::Render => self.render(tui)?,
@@ -156,6 +226,83 @@ impl App {
     Ok(())
   }
 
+  fn run_doctor(&self) -> Result<()> {
+    let diagnostics = doctor::diagnose();
+    if diagnostics.is_empty() {
+      self
+        .action_tx
+        .send(Action::Notify("No problems found".to_string()))?;
+    }
+    self.report(&diagnostics)
+  }
+
+  fn open_file(&self, tui: &mut Tui, location: &FileLocation) -> Result<()> {
+    let Some(editor) = editor::editor(self.config.config.editor.as_deref()) else {
+      self.action_tx.send(Action::Error(
+        "No editor to open files in, set $EDITOR or `editor` in the config".to_string(),
+      ))?;
+      return Ok(());
+    };
+    let command = EditorCommand::new(&editor, &self.config.config.editors, location);
+    info!("Opening {} with {command:?}", location.path.display());
+    // Terminal editors get the terminal to themselves, like while suspended.
+    if command.terminal {
+      tui.exit()?;
+    }
+    let result = command.run();
+    if command.terminal {
+      tui.enter()?;
+      self.action_tx.send(Action::ClearScreen)?;
+    }
+    if let Err(err) = result {
+      self.action_tx.send(Action::Error(err))?;
+    }
+    Ok(())
+  }
+
+  fn reload_config_if_due(&mut self) -> Result<()> {
+    if self
+      .reload_config_at
+      .is_none_or(|reload_at| Instant::now() < reload_at)
+    {
+      return Ok(());
+    }
+    self.reload_config_at = None;
+    // Styles and highlights that fail to parse would silently fall back to defaults.
+    let errors: Vec<String> = doctor::check_config()
+      .into_iter()
+      .filter(|diagnostic| diagnostic.severity >= Severity::Error)
+      .map(|diagnostic| diagnostic.message)
+      .collect();
+    if !errors.is_empty() {
+      let errors = errors.join("; ");
+      warn!("Keeping the previous configuration: {errors}");
+      self.action_tx.send(Action::Error(format!(
+        "Invalid configuration, keeping the previous one: {errors}"
+      )))?;
+      return Ok(());
+    }
+    match Config::new() {
+      Ok(config) => {
+        for component in self.components.iter_mut() {
+          component.register_config_handler(config.clone())?;
+        }
+        self.config = config;
+        info!("Configuration reloaded");
+        self
+          .action_tx
+          .send(Action::Notify("Configuration reloaded".to_string()))?;
+      }
+      Err(err) => {
+        warn!("Keeping the previous configuration: {err}");
+        self.action_tx.send(Action::Error(format!(
+          "Invalid configuration, keeping the previous one: {err}"
+        )))?;
+      }
+    }
+    Ok(())
+  }
+
   fn handle_resize(&mut self, tui: &mut Tui, w: u16, h: u16) -> Result<()> {
     tui.resize(Rect::new(0, 0, w, h))?;
     self.render(tui)?;
@@ -175,3 +322,11 @@ impl App {
     Ok(())
   }
 }
+
+/// Shows each diagnostic as an error toast.
+fn report(action_tx: &mpsc::UnboundedSender<Action>, diagnostics: &[Diagnostic]) -> Result<()> {
+  for diagnostic in diagnostics {
+    action_tx.send(Action::Error(diagnostic.to_string()))?;
+  }
+  Ok(())
+}
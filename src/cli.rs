@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
 use crate::config::{get_config_dir, get_data_dir};
 
@@ -12,6 +12,15 @@ pub struct Cli {
   /// Frame rate, i.e. number of frames per second
   #[arg(short, long, value_name = "FLOAT", default_value_t = 60.0)]
   pub frame_rate: f64,
+
+  #[command(subcommand)]
+  pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+  /// Check the configuration and workspace for problems and suggest fixes
+  Doctor,
 }
 
 const VERSION_MESSAGE: &str = concat!(
@@ -0,0 +1,373 @@
+use strum::VariantNames;
+
+use crate::{
+  action::{Action, ProjectCommand, ProjectScript},
+  layout::LayoutPreset,
+  project_manager::Project,
+  search::{History, ProjectSort, QUALIFIERS},
+  theme::Theme,
+};
+
+/// Commands of the `:` command line; unique prefixes such as `:q` are accepted too.
+pub const COMMANDS: [&str; 21] = [
+  "run", "start", "build", "stop", "restart", "filter", "sort", "timeline", "problems", "ports",
+  "interact", "shell", "theme", "layout", "zoom", "set", "help", "palette", "reload", "doctor",
+  "quit",
+];
+
+/// Options toggled with `:set <option>`, `:set no<option>` and `:set <option>!`.
+pub const OPTIONS: [&str; 1] = ["follow"];
+
+/// Parses a command line into actions; commands can be chained with `|`.
+pub fn parse(line: &str) -> Result<Vec<Action>, String> {
+  line
+    .split('|')
+    .map(str::trim)
+    .filter(|command| !command.is_empty())
+    .map(parse_command)
+    .collect()
+}
+
+fn resolve(name: &str) -> Result<&'static str, String> {
+  if let Some(command) = COMMANDS.iter().find(|command| **command == name) {
+    return Ok(command);
+  }
+  let candidates: Vec<&str> = COMMANDS
+    .into_iter()
+    .filter(|command| command.starts_with(name))
+    .collect();
+  match candidates[..] {
+    [command] => Ok(command),
+    [] => Err(format!("Unknown command `{name}`")),
+    _ => Err(format!(
+      "Ambiguous command `{name}`, could be: {}",
+      candidates.join(", ")
+    )),
+  }
+}
+
+fn parse_command(command: &str) -> Result<Action, String> {
+  let mut words = command.split_whitespace();
+  let name = resolve(words.next().unwrap_or_default())?;
+  let args: Vec<&str> = words.collect();
+  let action = match (name, &args[..]) {
+    ("run", [project, script]) => Action::RunScript(project.to_string(), script.to_string()),
+    ("start", []) => Action::ProjectScript(ProjectScript::Start),
+    ("build", []) => Action::ProjectScript(ProjectScript::Build),
+    ("start" | "build", [project]) => Action::RunScript(project.to_string(), name.to_string()),
+    ("stop", []) => Action::ProjectCommand(ProjectCommand::Stop),
+    ("stop", ["all"]) => Action::ProjectCommand(ProjectCommand::StopAll),
+    ("stop", [project]) => Action::StopProject(project.to_string()),
+    ("restart", []) => Action::Restart,
+    ("restart", [project]) => Action::RestartProject(project.to_string()),
+    ("filter", query) => Action::Filter(query.join(" ")),
+    ("sort", [sort]) => Action::SortProjects(sort.parse().map_err(|_| {
+      format!(
+        "Unknown order `{sort}`, expected one of: {}",
+        ProjectSort::VARIANTS.join(", ")
+      )
+    })?),
+    ("timeline", []) => Action::ToggleTimeline,
+    ("timeline", ["all"]) => Action::TimelineProjects(Vec::new()),
+    ("timeline", ["only", projects @ ..]) if !projects.is_empty() => {
+      Action::TimelineProjects(projects.iter().map(|project| project.to_string()).collect())
+    }
+    ("timeline", ["grep", text @ ..]) => Action::TimelineFilter(text.join(" ")),
+    ("problems", []) => Action::ToggleProblems,
+    ("ports", []) => Action::TogglePorts,
+    ("ports", [verb @ ("kill" | "open" | "copy"), port]) => {
+      let port = port.parse().map_err(|_| format!("Invalid port `{port}`"))?;
+      match *verb {
+        "kill" => Action::KillPort(port),
+        "open" => Action::OpenPort(port),
+        _ => Action::CopyPort(port),
+      }
+    }
+    ("interact", []) => Action::Interact,
+    ("shell", []) => Action::OpenShell(None),
+    ("shell", [project]) => Action::OpenShell(Some(project.to_string())),
+    ("theme", [theme]) => Action::SetTheme(theme.to_string()),
+    ("layout", [layout]) => Action::SetLayout(layout.parse().map_err(|_| {
+      format!(
+        "Unknown layout `{layout}`, expected one of: {}",
+        LayoutPreset::VARIANTS.join(", ")
+      )
+    })?),
+    ("zoom", []) => Action::ToggleZoom,
+    ("set", [option]) => parse_option(option)?,
+    ("help", []) => Action::Help,
+    ("palette", []) => Action::CommandPalette,
+    ("reload", []) => Action::ReloadConfig,
+    ("doctor", []) => Action::Doctor,
+    ("quit", []) => Action::Quit,
+    _ => return Err(format!("Usage: {}", usage(name))),
+  };
+  Ok(action)
+}
+
+fn parse_option(option: &str) -> Result<Action, String> {
+  let (name, value) = if let Some(name) = option.strip_suffix('!') {
+    (name, None)
+  } else if let Some(name) = option
+    .strip_prefix("no")
+    .filter(|name| OPTIONS.contains(name))
+  {
+    (name, Some(false))
+  } else {
+    (option, Some(true))
+  };
+  if !OPTIONS.contains(&name) {
+    return Err(format!(
+      "Unknown option `{name}`, expected one of: {}",
+      OPTIONS.join(", ")
+    ));
+  }
+  Ok(Action::SetOption(name.to_string(), value))
+}
+
+fn usage(command: &str) -> &'static str {
+  match command {
+    "run" => ":run <project> <script>",
+    "start" => ":start [project]",
+    "build" => ":build [project]",
+    "stop" => ":stop [project|all]",
+    "restart" => ":restart [project]",
+    "filter" => ":filter [query]",
+    "sort" => ":sort <order>",
+    "timeline" => ":timeline [all|only <project>...|grep [text]]",
+    "problems" => ":problems",
+    "ports" => ":ports [kill|open|copy <port>]",
+    "interact" => ":interact",
+    "shell" => ":shell [project]",
+    "theme" => ":theme <name>",
+    "layout" => ":layout <preset>",
+    "zoom" => ":zoom",
+    "set" => ":set [no]<option>[!]",
+    "help" => ":help",
+    "palette" => ":palette",
+    "reload" => ":reload",
+    "doctor" => ":doctor",
+    _ => ":quit",
+  }
+}
+
+/// Completions of the last word of `line`, each returned as the full completed line.
+pub fn complete(line: &str, projects: &[Project]) -> Vec<String> {
+  // The last command of a chain is the one being completed.
+  let command = line.rsplit('|').next().unwrap_or_default();
+  let prefix = &line[..line.len() - command.len()];
+  let mut words: Vec<&str> = command.split_whitespace().collect();
+  let partial = if command.ends_with(char::is_whitespace) || words.is_empty() {
+    ""
+  } else {
+    words.pop().unwrap_or_default()
+  };
+
+  let project_names = || projects.iter().map(|project| project.name.clone());
+  let candidates: Vec<String> = match (words.first().map(|name| resolve(name)), words.len()) {
+    (None, _) => COMMANDS.iter().map(|command| command.to_string()).collect(),
+    (Some(Ok("run" | "start" | "build" | "restart" | "shell")), 1) => project_names().collect(),
+    (Some(Ok("stop")), 1) => std::iter::once("all".to_string())
+      .chain(project_names())
+      .collect(),
+    (Some(Ok("run")), 2) => projects
+      .iter()
+      .find(|project| project.name == words[1])
+      .map(|project| project.commands.clone())
+      .unwrap_or_default(),
+    (Some(Ok("timeline")), 1) => ["all", "only", "grep"].map(str::to_string).to_vec(),
+    (Some(Ok("ports")), 1) => ["kill", "open", "copy"].map(str::to_string).to_vec(),
+    (Some(Ok("timeline")), len) if len > 1 && words[1] == "only" => project_names().collect(),
+    (Some(Ok("theme")), 1) => Theme::names().map(str::to_string).collect(),
+    (Some(Ok("layout")), 1) => LayoutPreset::VARIANTS
+      .iter()
+      .map(|layout| layout.to_string())
+      .collect(),
+    (Some(Ok("sort")), 1) => ProjectSort::VARIANTS
+      .iter()
+      .map(|sort| sort.to_string())
+      .collect(),
+    (Some(Ok("set")), 1) => OPTIONS
+      .iter()
+      .flat_map(|option| [option.to_string(), format!("no{option}")])
+      .collect(),
+    (Some(Ok("filter")), _) => QUALIFIERS
+      .iter()
+      .map(|qualifier| format!("{qualifier}:"))
+      .collect(),
+    _ => Vec::new(),
+  };
+
+  let head: String = words.iter().map(|word| format!("{word} ")).collect();
+  let separator = if prefix.is_empty() || prefix.ends_with(' ') {
+    ""
+  } else {
+    " "
+  };
+  candidates
+    .into_iter()
+    .filter(|candidate| candidate.starts_with(partial))
+    .map(|candidate| format!("{prefix}{separator}{head}{candidate}"))
+    .collect()
+}
+
+/// State of the `:` command line at the bottom of the screen.
+#[derive(Debug, Default)]
+pub struct CommandLineState {
+  pub visible: bool,
+  pub input: String,
+  /// Completions cycled through with tab, and the one currently shown.
+  pub completions: Vec<String>,
+  pub completion: Option<usize>,
+  history: History,
+}
+
+impl CommandLineState {
+  pub fn open(&mut self) {
+    self.visible = true;
+    self.set_input(String::new());
+  }
+
+  pub fn close(&mut self) {
+    self.visible = false;
+    self.set_input(String::new());
+  }
+
+  pub fn set_input(&mut self, input: String) {
+    self.input = input;
+    self.completions.clear();
+    self.completion = None;
+  }
+
+  /// Cycles through the completions of the current input, backwards if `forward` is false.
+  pub fn complete(&mut self, projects: &[Project], forward: bool) {
+    if self.completion.is_none() {
+      self.completions = complete(&self.input, projects);
+    }
+    let len = self.completions.len();
+    if len == 0 {
+      return;
+    }
+    let next = match (self.completion, forward) {
+      (None, true) => 0,
+      (None, false) => len - 1,
+      (Some(current), true) => (current + 1) % len,
+      (Some(current), false) => (current + len - 1) % len,
+    };
+    self.completion = Some(next);
+    self.input = self.completions[next].clone();
+  }
+
+  pub fn recall(&mut self, previous: bool) {
+    let entry = if previous {
+      self.history.previous()
+    } else {
+      self.history.next()
+    };
+    if let Some(entry) = entry.map(str::to_string) {
+      self.set_input(entry);
+    }
+  }
+
+  /// Closes the command line and returns the submitted input.
+  pub fn submit(&mut self) -> String {
+    let input = self.input.trim().to_string();
+    self.history.push(&input);
+    self.close();
+    input
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn projects() -> Vec<Project> {
+    ["web-app", "web-api"]
+      .into_iter()
+      .map(|name| Project {
+        name: name.to_string(),
+        commands: vec!["test".to_string(), "typecheck".to_string()],
+        ..Default::default()
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_parse() {
+    assert_eq!(
+      parse("run web-app test"),
+      Ok(vec![Action::RunScript(
+        "web-app".to_string(),
+        "test".to_string()
+      )])
+    );
+    assert_eq!(
+      parse("stop all | th light"),
+      Ok(vec![
+        Action::ProjectCommand(ProjectCommand::StopAll),
+        Action::SetTheme("light".to_string()),
+      ])
+    );
+    assert_eq!(
+      parse("filter status:error web"),
+      Ok(vec![Action::Filter("status:error web".to_string())])
+    );
+    assert_eq!(
+      parse("set nofollow"),
+      Ok(vec![Action::SetOption("follow".to_string(), Some(false))])
+    );
+    assert_eq!(
+      parse("set follow!"),
+      Ok(vec![Action::SetOption("follow".to_string(), None)])
+    );
+    assert_eq!(
+      parse("timeline only web-app api-mock"),
+      Ok(vec![Action::TimelineProjects(vec![
+        "web-app".to_string(),
+        "api-mock".to_string()
+      ])])
+    );
+    assert_eq!(
+      parse("so memory"),
+      Ok(vec![Action::SortProjects(ProjectSort::Memory)])
+    );
+    assert!(parse("sort size").unwrap_err().starts_with("Unknown order"));
+    assert_eq!(parse("q"), Ok(vec![Action::Quit]));
+    assert_eq!(parse("pr"), Ok(vec![Action::ToggleProblems]));
+    assert_eq!(parse("po kill 3000"), Ok(vec![Action::KillPort(3000)]));
+    assert!(parse("ports open x")
+      .unwrap_err()
+      .starts_with("Invalid port"));
+    assert!(parse("s").unwrap_err().starts_with("Ambiguous command"));
+    assert!(parse("run web-app").unwrap_err().starts_with("Usage"));
+    assert!(parse("set color").is_err());
+  }
+
+  #[test]
+  fn test_complete() {
+    let projects = projects();
+    assert_eq!(complete("st", &projects), vec!["start", "stop"]);
+    assert_eq!(
+      complete("run web-app t", &projects),
+      vec!["run web-app test", "run web-app typecheck"]
+    );
+    assert_eq!(
+      complete("stop all | run web-a", &projects),
+      vec!["stop all | run web-app", "stop all | run web-api"]
+    );
+    assert_eq!(
+      complete("set ", &projects),
+      vec!["set follow", "set nofollow"]
+    );
+
+    let mut command_line = CommandLineState::default();
+    command_line.open();
+    command_line.set_input("stop ".to_string());
+    command_line.complete(&projects, true);
+    command_line.complete(&projects, true);
+    assert_eq!(command_line.input, "stop web-app");
+    command_line.complete(&projects, false);
+    assert_eq!(command_line.input, "stop all");
+  }
+}
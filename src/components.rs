@@ -11,6 +11,8 @@ use crate::{action::Action, config::Config, tui::Event};
 pub mod fps;
 pub mod home;
 pub mod logo;
+pub mod toast;
+pub mod which_key;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 ///
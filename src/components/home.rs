@@ -1,21 +1,46 @@
 use super::{logo::Logo, Component};
 use crate::{
   action::{Action, ProjectCommand, ProjectScript::*},
-  config::Config,
-  project_manager::{Project, ProjectManager},
+  app::Mode as AppMode,
+  command::{self, CommandLineState},
+  config::{self, key_event_to_string, parse_key_sequence, Config},
+  editor::{self, FileLocation},
+  layout::{self, LayoutPreset},
+  log_search::{self, HighlightRule, LogPrompt, LogSearchState},
+  log_view::{self, LogView},
+  palette::{PaletteEntry, PaletteState},
+  ports::{self, PortsState},
+  problems::ProblemsState,
+  project_manager::{Project, ProjectManager, ProjectStatus as ProjectStatusKind, ScanEvent},
+  pty::Pty,
+  restart::RestartState,
+  sampler::{Usage, UsageHistory},
+  search::{self, History, ProjectSort, Query, SearchKind},
+  shell::{ShellState, ShellTab},
+  theme::Theme,
+  timeline::TimelineState,
   ui::{
-    project_detail::ProjectDetail, project_list::ProjectList, project_status::ProjectStatus, UI,
+    command_line::CommandLine, help::Help, log_grid::LogGrid, palette::Palette, ports::PortsView,
+    problems::ProblemsView, project_detail::ProjectDetail, project_list::ProjectList,
+    project_status::ProjectStatus, shell::ShellView, timeline::TimelineView, UI,
   },
+  watch::{self, IgnoreRules, ProjectWatcher, Rebuild, RebuildQueue},
+  workspace::{self, WorkspaceWatcher},
 };
 use color_eyre::Result;
 use ratatui::{
-  crossterm::event::{KeyCode, KeyEvent},
+  crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
   prelude::*,
-  widgets::{Block, Borders},
+  widgets::{Block, Borders, Clear},
 };
-use std::sync::{mpsc::Receiver, Arc};
-use strum::Display;
-use tokio::sync::mpsc::UnboundedSender;
+use std::{
+  collections::HashMap,
+  path::PathBuf,
+  sync::{mpsc::Receiver, Arc},
+  time::{Duration, Instant},
+};
+use strum::{Display, VariantNames};
+use tokio::sync::mpsc::{error::TryRecvError, UnboundedReceiver, UnboundedSender};
 
 #[derive(Default, PartialEq, Display, Debug)]
 pub enum Mode {
@@ -32,20 +57,115 @@ pub enum ActiveComponent {
   Status,
 }
 
+/// Most projects whose logs can be pinned to the log grid at once.
+pub const MAX_LOG_PANES: usize = 4;
+
+/// A project's output pinned to the log grid, scrolled independently of the others.
+#[derive(Clone, Debug)]
+pub struct LogPane {
+  pub project: usize,
+  pub view: LogView,
+}
+
+/// Keys forwarded to a script running in a terminal.
+#[derive(Default, Debug)]
+pub struct InteractState {
+  /// The project whose script receives the keys.
+  pub project: Option<usize>,
+  /// The key that stops forwarding, from the `interact_escape` config.
+  pub escape: Option<KeyEvent>,
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct HelpEntry {
+  pub keys: String,
+  pub description: String,
+}
+
+#[derive(Default, Debug)]
+pub struct HelpState {
+  pub visible: bool,
+  pub title: String,
+  pub entries: Vec<HelpEntry>,
+  pub query: String,
+  pub scroll: usize,
+}
+
+impl HelpState {
+  pub fn filtered_entries(&self) -> Vec<&HelpEntry> {
+    let query = self.query.to_lowercase();
+    self
+      .entries
+      .iter()
+      .filter(|entry| {
+        entry.keys.to_lowercase().contains(&query)
+          || entry.description.to_lowercase().contains(&query)
+      })
+      .collect()
+  }
+
+  pub fn scroll_by(&mut self, delta: i32) {
+    let max = self.filtered_entries().len().saturating_sub(1);
+    self.scroll = ((self.scroll as i32 + delta).max(0) as usize).min(max);
+  }
+}
+
 #[derive(Default, Debug)]
 pub struct AppState {
-  pub projects: Vec<Project>,        // Owned data
-  pub filtered_projects: Vec<usize>, // Indices to the projects vector
+  pub projects: Vec<Project>,          // Owned data
+  pub filtered_projects: Vec<usize>,   // Indices to the projects vector, best match first
+  pub search_matches: Vec<Vec<usize>>, // Matched name characters of each filtered project
   pub selected_project_index: usize,
   pub search_query: String,
+  pub sort: ProjectSort,
+  /// Recent CPU and memory use of the running projects, by name.
+  pub usage: HashMap<String, UsageHistory>,
+  pub search_history: HashMap<SearchKind, History>,
   pub mode: Mode,
   pub active_component: ActiveComponent,
   pub detail_scroll: usize,
-  pub log_scroll: usize,
+  /// Scroll position of the selected project's output, following it unless `:set nofollow`.
+  pub log_view: LogView,
   pub logo: Logo,
+  pub help: HelpState,
+  pub palette: PaletteState,
+  pub command_line: CommandLineState,
+  /// Pinned logs shown instead of the selected project's status, and the focused one.
+  pub log_panes: Vec<LogPane>,
+  pub focused_pane: usize,
+  /// The merged log of all projects, shown instead of pinned logs when visible.
+  pub timeline: TimelineState,
+  /// Diagnostics parsed from the output of all projects, shown instead of any log when visible.
+  pub problems: ProblemsState,
+  /// Listening TCP ports, shown instead of any other view when visible.
+  pub ports: PortsState,
+  pub interact: InteractState,
+  /// Shells in project directories, shown instead of the dashboard when visible.
+  pub shell: ShellState,
+  /// Automatic restarts of each project, by index into `projects`.
+  pub restarts: HashMap<usize, RestartState>,
+  /// Search and filter of the output views.
+  pub log_search: LogSearchState,
+  pub highlights: Vec<HighlightRule>,
+  pub theme: Theme,
 }
 
 impl AppState {
+  /// Index into `projects` of the selected project.
+  pub fn selected_project(&self) -> Option<usize> {
+    self
+      .filtered_projects
+      .get(self.selected_project_index)
+      .copied()
+  }
+
+  pub fn project_by_name(&self, name: &str) -> Option<usize> {
+    self
+      .projects
+      .iter()
+      .position(|project| project.name == name)
+  }
+
   pub fn get_selected_project(&self) -> Option<&Project> {
     if let Some(&project_index) = self.filtered_projects.get(self.selected_project_index) {
       self.projects.get(project_index)
@@ -55,39 +175,265 @@ impl AppState {
   }
 
   pub fn update_filtered_projects(&mut self) {
-    self.filtered_projects = self
-      .projects
-      .iter()
-      .enumerate()
-      .filter(|(_, project)| {
-        // Example filtering logic
-        project.name.contains(&self.search_query)
-      })
-      .map(|(index, _)| index)
-      .collect();
+    let matches = search::search(&self.projects, &Query::parse(&self.search_query));
+    self.filtered_projects = matches.iter().map(|m| m.index).collect();
+    self.search_matches = matches.into_iter().map(|m| m.indices).collect();
+    self.sort_filtered_projects();
     self.selected_project_index = 0;
   }
 
+  /// The latest CPU and memory use of a running project.
+  pub fn usage_of(&self, project_index: usize) -> Option<Usage> {
+    self
+      .usage
+      .get(&self.projects[project_index].name)
+      .and_then(UsageHistory::latest)
+  }
+
+  /// Orders the filtered projects again, e.g. after new usage samples, keeping the selection.
+  pub fn resort_projects(&mut self) {
+    let selected = self
+      .selected_project()
+      .map(|index| self.projects[index].name.clone());
+    self.sort_filtered_projects();
+    self.reselect(selected);
+  }
+
+  /// Orders the filtered projects as `sort` says; equal ones keep the search order.
+  fn sort_filtered_projects(&mut self) {
+    let key = match self.sort {
+      ProjectSort::None => return,
+      ProjectSort::Cpu => |usage: Usage| usage.cpu as f64,
+      ProjectSort::Memory => |usage: Usage| usage.memory as f64,
+    };
+    let mut rows: Vec<(usize, Vec<usize>)> = self
+      .filtered_projects
+      .drain(..)
+      .zip(self.search_matches.drain(..))
+      .collect();
+    rows.sort_by(|(a, _), (b, _)| {
+      let a = self.usage_of(*a).map(key);
+      let b = self.usage_of(*b).map(key);
+      // Projects without usage sort last.
+      b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal)
+    });
+    (self.filtered_projects, self.search_matches) = rows.into_iter().unzip();
+  }
+
+  /// Replaces the input of `kind` with an older (`previous`) or newer entry from its history.
+  pub fn recall_search(&mut self, kind: SearchKind, previous: bool) -> Option<String> {
+    let history = self.search_history.entry(kind).or_default();
+    let entry = if previous {
+      history.previous()
+    } else {
+      history.next()
+    };
+    entry.map(String::from)
+  }
+
   pub fn navigate(&mut self, direction: i32) {
-    match self.active_component {
+    self.scroll(self.active_component.clone(), direction);
+  }
+
+  /// The terminal of `project` if keys are forwarded to it.
+  pub fn interacting_pty(&self, project: Option<usize>) -> Option<&Pty> {
+    if project.is_none() || self.interact.project != project {
+      return None;
+    }
+    self.projects[project?].pty.as_ref()
+  }
+
+  /// Merges rescanned projects into the list, keeping running scripts, the search and the
+  /// selection. Returns the new index of each old project.
+  pub fn refresh_projects(&mut self, discovered: Vec<Project>) -> Vec<Option<usize>> {
+    let selected = self
+      .selected_project()
+      .map(|index| self.projects[index].name.clone());
+    let remap = workspace::merge(&mut self.projects, discovered);
+    let moved = |index: usize| remap.get(index).copied().flatten();
+
+    self.log_panes.retain_mut(|pane| match moved(pane.project) {
+      Some(index) => {
+        pane.project = index;
+        true
+      }
+      None => false,
+    });
+    self.focused_pane = self
+      .focused_pane
+      .min(self.log_panes.len().saturating_sub(1));
+    self.interact.project = self.interact.project.and_then(moved);
+    self.restarts = self
+      .restarts
+      .drain()
+      .filter_map(|(index, restart)| Some((moved(index)?, restart)))
+      .collect();
+    let projects = &self.projects;
+    self
+      .problems
+      .retain(|name| projects.iter().any(|project| project.name == name));
+
+    self.update_filtered_projects();
+    self.reselect(selected);
+    remap
+  }
+
+  /// Adds a project found by a scan, keeping the search and the selection.
+  pub fn add_project(&mut self, project: Project) {
+    let selected = self
+      .selected_project()
+      .map(|index| self.projects[index].name.clone());
+    self.projects.push(project);
+    self.update_filtered_projects();
+    self.reselect(selected);
+  }
+
+  /// Selects the named project again after the list changed, or the first one.
+  fn reselect(&mut self, name: Option<String>) {
+    self.selected_project_index = name
+      .and_then(|name| {
+        self
+          .filtered_projects
+          .iter()
+          .position(|&index| self.projects[index].name == name)
+      })
+      .unwrap_or_default();
+  }
+
+  /// Parses the problems in the lines the projects printed since the last update.
+  pub fn update_problems(&mut self) {
+    for project in &self.projects {
+      // Copy the new lines out, so the output forwarders don't wait for the parsing.
+      let (offset, lines) = {
+        let output = project.output.lock().unwrap();
+        let (offset, lines) = self.problems.unparsed(&project.name, &output);
+        (offset, lines.to_string())
+      };
+      self.problems.update(&project.name, offset, &lines);
+    }
+  }
+
+  /// Moves the selection of the project list, or scrolls the other panels.
+  pub fn scroll(&mut self, component: ActiveComponent, direction: i32) {
+    match component {
       ActiveComponent::List => {
-        let len = self.filtered_projects.len();
-        if direction > 0 && self.selected_project_index < len - 1 {
-          self.selected_project_index += 1;
-        } else if direction < 0 && self.selected_project_index > 0 {
-          self.selected_project_index -= 1;
-        }
+        let last = self.filtered_projects.len().saturating_sub(1) as i32;
+        self.selected_project_index =
+          (self.selected_project_index as i32 + direction).clamp(0, last) as usize;
       }
       ActiveComponent::Detail => {
         self.detail_scroll = (self.detail_scroll as i32 + direction).max(0) as usize;
       }
+      ActiveComponent::Status if self.ports.visible => self.ports.select_by(direction),
+      ActiveComponent::Status if self.problems.visible => self.problems.select_by(direction),
       ActiveComponent::Status => {
-        tracing::info!("log_scroll: {}", self.log_scroll);
-        self.log_scroll = (self.log_scroll as i32 + direction).max(0) as usize;
-      } // _ => {}
+        if let Some((view, len)) = self.focused_log() {
+          view.scroll_by(len, direction);
+        }
+      }
     }
   }
 
+  /// Jumps to the first or last project, or the top or the followed end of a log.
+  pub fn scroll_to_end(&mut self, component: ActiveComponent, top: bool) {
+    match component {
+      ActiveComponent::List if top => self.selected_project_index = 0,
+      ActiveComponent::List => {
+        self.selected_project_index = self.filtered_projects.len().saturating_sub(1)
+      }
+      ActiveComponent::Detail if top => self.detail_scroll = 0,
+      ActiveComponent::Detail => {}
+      ActiveComponent::Status if self.ports.visible => {
+        self.ports.selected = if top {
+          0
+        } else {
+          self.ports.ports.len().saturating_sub(1)
+        }
+      }
+      ActiveComponent::Status if self.problems.visible => {
+        self.problems.selected = if top {
+          0
+        } else {
+          self.problems.all().len().saturating_sub(1)
+        }
+      }
+      ActiveComponent::Status => {
+        if let Some((view, len)) = self.focused_log() {
+          if top {
+            view.scroll_to(len, 0);
+          } else {
+            view.set_follow(len, true);
+          }
+        }
+      }
+    }
+  }
+
+  /// The project whose output the status panel shows or, with pinned logs, the focused one.
+  pub fn focused_output_project(&self) -> Option<usize> {
+    match self.log_panes.get(self.focused_pane) {
+      Some(pane) => Some(pane.project),
+      None => self.selected_project(),
+    }
+  }
+
+  /// The log the status panel scrolls, with its length in rows.
+  pub fn focused_log(&mut self) -> Option<(&mut LogView, usize)> {
+    if self.timeline.visible {
+      let len = self.timeline.with_lines(|lines| lines.len());
+      return Some((&mut self.timeline.view, len));
+    }
+    let project = self.focused_output_project()?;
+    let view = match self.log_panes.get_mut(self.focused_pane) {
+      Some(pane) => &mut pane.view,
+      None => &mut self.log_view,
+    };
+    let len = {
+      let output = self.projects[project].output.lock().unwrap();
+      view.rows(&self.log_search.visible_lines(&output)).len()
+    };
+    Some((view, len))
+  }
+
+  /// Pins the selected project's log to the grid, or unpins it.
+  pub fn toggle_pin(&mut self) -> Result<(), String> {
+    let Some(project) = self.selected_project() else {
+      return Ok(());
+    };
+    if let Some(index) = self
+      .log_panes
+      .iter()
+      .position(|pane| pane.project == project)
+    {
+      self.log_panes.remove(index);
+      self.focused_pane = self
+        .focused_pane
+        .min(self.log_panes.len().saturating_sub(1));
+      return Ok(());
+    }
+    if self.log_panes.len() == MAX_LOG_PANES {
+      return Err(format!("At most {MAX_LOG_PANES} logs can be pinned"));
+    }
+    self.log_panes.push(LogPane {
+      project,
+      view: LogView::default(),
+    });
+    self.focused_pane = self.log_panes.len() - 1;
+    Ok(())
+  }
+
+  pub fn focus_pane(&mut self, next: bool) {
+    let len = self.log_panes.len();
+    if len == 0 {
+      return;
+    }
+    self.focused_pane = if next {
+      (self.focused_pane + 1) % len
+    } else {
+      (self.focused_pane + len - 1) % len
+    };
+  }
+
   pub fn switch_active_component(&mut self, next: bool) {
     self.active_component = match (self.active_component.clone(), next) {
       (ActiveComponent::List, true) => ActiveComponent::Detail,
@@ -108,38 +454,1046 @@ impl AppState {
   }
 }
 
+/// Actions `handle_key_event` emits for single keys in normal mode.
+const LOCAL_ACTION_KEYS: [(&str, Action); 5] = [
+  ("s", Action::ProjectScript(Start)),
+  ("b", Action::ProjectScript(Build)),
+  ("c", Action::ProjectCommand(ProjectCommand::Stop)),
+  ("r", Action::Restart),
+  ("?", Action::Help),
+];
+
+/// Lines the details and output panels move per mouse wheel step.
+const WHEEL_STEP: i32 = 3;
+/// Narrowest a panel can be dragged, in percent of the screen width.
+const MIN_PANEL_WIDTH: u16 = 10;
+
 pub struct Home {
   command_tx: Option<UnboundedSender<Action>>,
   config: Config,
   state: AppState,
   manager: ProjectManager,
+  /// Widths of the list, details and status panels in percent.
+  panel_widths: [u16; 3],
+  /// Where the panels were drawn last, for mapping mouse events.
+  panel_areas: [Rect; 3],
+  /// The panel border being dragged: 0 left of the details, 1 right of them.
+  dragged_border: Option<usize>,
+  layout: LayoutPreset,
+  /// Whether the focused panel fills the screen.
+  zoomed: bool,
+  /// Watches the files of projects with `"watch": true`.
+  watcher: Option<ProjectWatcher>,
+  rebuilds: RebuildQueue,
+  /// The rebuild running, one at a time so that dependencies finish first.
+  rebuild: Option<Rebuild>,
+  /// Notices projects being added, removed or edited.
+  workspace_watcher: Option<WorkspaceWatcher>,
+  /// The running workspace scan and the projects it found so far.
+  scan: Option<(UnboundedReceiver<ScanEvent>, Vec<Project>)>,
+  /// Whether the listening ports are being listed in the background.
+  listing_ports: bool,
+  /// The port to free once the ports listed, to kill its current owner.
+  kill_port_when_listed: Option<u16>,
 }
 
 impl Home {
   pub fn default() -> Self {
-    let mut state = AppState::default();
-    let (manager, projects) = Self::initialize();
-
-    state.projects = projects;
-    state.update_filtered_projects();
+    let manager = ProjectManager::default();
+    let scan = Some((manager.scan(), Vec::new()));
+    let workspace_watcher = Self::watch_workspace(&manager, &[]);
 
+    let config = Config::default();
     Self {
-      state,
+      state: AppState {
+        theme: Theme::from_config(&config),
+        ..Default::default()
+      },
       command_tx: None,
-      config: Default::default(),
+      config,
       manager,
+      panel_widths: [25, 30, 45],
+      panel_areas: Default::default(),
+      dragged_border: None,
+      layout: LayoutPreset::default(),
+      zoomed: false,
+      watcher: None,
+      rebuilds: RebuildQueue::default(),
+      rebuild: None,
+      workspace_watcher,
+      scan,
+      listing_ports: false,
+      kill_port_when_listed: None,
     }
   }
 
-  fn initialize() -> (ProjectManager, Vec<Project>) {
-    let manager = ProjectManager::default();
-    let projects = manager.get_projects();
-    (manager, projects)
+  fn watch_workspace(manager: &ProjectManager, projects: &[Project]) -> Option<WorkspaceWatcher> {
+    let dirs: Vec<PathBuf> = projects
+      .iter()
+      .map(|project| manager.base_path.join(&project.path))
+      .collect();
+    WorkspaceWatcher::new(&manager.base_path, &dirs)
+      .inspect_err(|err| tracing::warn!("Not watching the workspace: {err}"))
+      .ok()
+  }
+
+  /// Adds the projects a running scan finds to the list, and merges the whole list once it
+  /// finished. Changes to the workspace start another scan.
+  fn refresh_projects(&mut self) {
+    if self
+      .workspace_watcher
+      .as_ref()
+      .is_some_and(WorkspaceWatcher::changed)
+    {
+      self.scan = Some((self.manager.scan(), Vec::new()));
+    }
+    let Some((rx, found)) = &mut self.scan else {
+      return;
+    };
+    loop {
+      match rx.try_recv() {
+        Ok(ScanEvent::Started(total)) => self.state.logo.progress = (0, total),
+        Ok(ScanEvent::Scanned(project)) => {
+          self.state.logo.progress.0 += 1;
+          if let Some(project) = project {
+            if let Some(pid) = project.pid {
+              self.manager.sampler.track(pid);
+            }
+            if self.state.project_by_name(&project.name).is_none() {
+              self.state.add_project(project.clone());
+            }
+            found.push(project);
+          }
+        }
+        Err(TryRecvError::Empty) => return,
+        Err(TryRecvError::Disconnected) => break,
+      }
+    }
+    let Some((_, found)) = self.scan.take() else {
+      return;
+    };
+    let remap = self.state.refresh_projects(found);
+    self.rebuilds.remap(&remap);
+    if let Some(rebuild) = &mut self.rebuild {
+      match remap.get(rebuild.project).copied().flatten() {
+        Some(index) => rebuild.project = index,
+        None => {
+          rebuild.cancel();
+          self.rebuild = None;
+        }
+      }
+    }
+    self.workspace_watcher = Self::watch_workspace(&self.manager, &self.state.projects);
+    self.start_watching();
+  }
+
+  /// Keys handled directly by `handle_key_event` for the current mode and focused panel.
+  fn local_keybindings(&self) -> Vec<(&'static str, &'static str)> {
+    match self.state.mode {
+      Mode::Normal => {
+        let (down, up) = match self.state.active_component {
+          ActiveComponent::List => ("Select next project", "Select previous project"),
+          ActiveComponent::Detail => ("Scroll details down", "Scroll details up"),
+          ActiveComponent::Status => ("Scroll output down", "Scroll output up"),
+        };
+        vec![
+          ("j / ↓", down),
+          ("k / ↑", up),
+          ("l / →", "Focus next panel"),
+          ("h / ←", "Focus previous panel"),
+          (
+            "/",
+            if self.state.active_component == ActiveComponent::Status {
+              "Search the output for a regex"
+            } else {
+              "Search projects"
+            },
+          ),
+          ("n / N", "Jump to the next or previous output match"),
+          ("pgup / pgdn", "Scroll a page up or down"),
+          ("ctrl-u / ctrl-d", "Scroll half a page up or down"),
+          (
+            "&",
+            "Only show output lines matching a regex, or not matching `!regex`",
+          ),
+          (":", "Open the command line"),
+          ("p", "Pin or unpin the selected project's log"),
+          ("tab / shift-tab", "Focus the next or previous pinned log"),
+          ("f", "Follow new output, or stop following it"),
+          ("t", "Show or hide the merged timeline of all logs"),
+          ("m", "Add or remove the selected project in the timeline"),
+          ("e", "Show or hide the problems found in the output"),
+          ("P", "Show or hide the listening ports"),
+          (
+            "x / o / y",
+            "Kill the owner of the selected port, or open or copy its URL",
+          ),
+          (
+            "i",
+            "Send keys to the focused output's script until the interact escape key",
+          ),
+          (
+            "`",
+            "Show the shell tabs, or a new shell in the selected project",
+          ),
+          ("T", "Open another shell tab in the selected project"),
+          (
+            "o",
+            "Open the selected problem or the file:line in the output in $EDITOR",
+          ),
+          ("s", "Start the selected project"),
+          ("b", "Build the selected project"),
+          ("c", "Stop the selected project"),
+          ("r", "Restart the selected project's last script"),
+          ("?", "Show this help"),
+        ]
+      }
+      Mode::Search => vec![
+        ("enter / esc", "Leave search, keeping the filter"),
+        ("backspace", "Delete the last character"),
+        ("↑ / ctrl-p", "Previous search"),
+        ("↓ / ctrl-n", "Next search"),
+        (
+          "status:<status>",
+          "Only projects with this status, e.g. status:running",
+        ),
+        ("dep:<name>", "Only projects depending on a package"),
+        ("script:<name>", "Only projects with a package.json script"),
+        ("tag:<keyword>", "Only projects with a package.json keyword"),
+        ("path:<prefix>", "Only projects below a directory"),
+      ],
+    }
+  }
+
+  fn toggle_help(&mut self) -> Option<Action> {
+    if self.state.help.visible {
+      let query = std::mem::take(&mut self.state.help).query;
+      self
+        .state
+        .search_history
+        .entry(SearchKind::Help)
+        .or_default()
+        .push(&query);
+      return Some(Action::EnterMode(self.input_mode()));
+    }
+
+    // Only the bindings of the app mode in effect apply, e.g. none of `Home` while searching.
+    let mut global: Vec<HelpEntry> = self
+      .config
+      .keybindings
+      .get(&self.input_mode())
+      .into_iter()
+      .flatten()
+      .map(|(keys, action)| HelpEntry {
+        keys: keys
+          .iter()
+          .map(|key| format!("<{}>", key_event_to_string(key)))
+          .collect(),
+        description: format!("{action:?}"),
+      })
+      .collect();
+    global.sort_by(|a, b| a.keys.cmp(&b.keys));
+
+    let mut entries: Vec<HelpEntry> = self
+      .local_keybindings()
+      .into_iter()
+      .map(|(keys, description)| HelpEntry {
+        keys: keys.to_string(),
+        description: description.to_string(),
+      })
+      .collect();
+    entries.extend(global);
+
+    self.state.help = HelpState {
+      visible: true,
+      title: format!(
+        "Help: {} mode, {} panel",
+        self.state.mode, self.state.active_component
+      ),
+      entries,
+      ..Default::default()
+    };
+    Some(Action::EnterMode(AppMode::Input))
+  }
+
+  /// The keys bound to `action`, either in the config or locally.
+  fn key_hint(&self, action: &Action) -> Option<String> {
+    let configured = self
+      .config
+      .keybindings
+      .get(&AppMode::Home)
+      .into_iter()
+      .flatten()
+      .filter(|(_, bound)| *bound == action)
+      .map(|(keys, _)| {
+        keys
+          .iter()
+          .map(|key| format!("<{}>", key_event_to_string(key)))
+          .collect::<String>()
+      })
+      .min();
+    configured.or_else(|| {
+      LOCAL_ACTION_KEYS
+        .iter()
+        .find(|(_, bound)| bound == action)
+        .map(|(keys, _)| keys.to_string())
+    })
+  }
+
+  fn palette_entries(&self) -> Vec<PaletteEntry> {
+    let mut entries: Vec<(String, Action)> = [
+      ("Start selected project", Action::ProjectScript(Start)),
+      ("Build selected project", Action::ProjectScript(Build)),
+      (
+        "Stop selected project",
+        Action::ProjectCommand(ProjectCommand::Stop),
+      ),
+      ("Restart selected project", Action::Restart),
+      (
+        "Stop all projects",
+        Action::ProjectCommand(ProjectCommand::StopAll),
+      ),
+      ("Toggle zoom of the focused panel", Action::ToggleZoom),
+      ("Show merged timeline of all logs", Action::ToggleTimeline),
+      ("Show problems of all projects", Action::ToggleProblems),
+      ("Show listening ports", Action::TogglePorts),
+      ("Send keys to the focused script", Action::Interact),
+      ("Show shell tabs", Action::ToggleShell),
+      (
+        "Open a shell in the selected project",
+        Action::OpenShell(None),
+      ),
+      ("Show help", Action::Help),
+      ("Reload configuration", Action::ReloadConfig),
+      ("Check configuration and workspace (doctor)", Action::Doctor),
+      ("Clear screen", Action::ClearScreen),
+      ("Suspend", Action::Suspend),
+      ("Quit", Action::Quit),
+    ]
+    .into_iter()
+    .map(|(label, action)| (label.to_string(), action))
+    .collect();
+    entries.extend(ProjectSort::VARIANTS.iter().filter_map(|name| {
+      let sort = name.parse().ok()?;
+      Some((format!("Sort projects: {name}"), Action::SortProjects(sort)))
+    }));
+    entries.extend(LayoutPreset::VARIANTS.iter().filter_map(|name| {
+      let layout = name.parse().ok()?;
+      Some((format!("Layout: {name}"), Action::SetLayout(layout)))
+    }));
+    entries.extend(
+      Theme::names().map(|name| (format!("Theme: {name}"), Action::SetTheme(name.to_string()))),
+    );
+    for project in &self.state.projects {
+      entries.extend(project.commands.iter().map(|script| {
+        (
+          format!("Run {script} in {}", project.name),
+          Action::RunScript(project.name.clone(), script.clone()),
+        )
+      }));
+    }
+
+    entries
+      .into_iter()
+      .map(|(label, action)| PaletteEntry {
+        label,
+        keys: self.key_hint(&action),
+        action,
+      })
+      .collect()
+  }
+
+  fn toggle_palette(&mut self) -> Option<Action> {
+    if self.state.palette.visible {
+      self.state.palette.close();
+      return Some(Action::EnterMode(self.input_mode()));
+    }
+    let entries = self.palette_entries();
+    self.state.palette.open(entries);
+    Some(Action::EnterMode(AppMode::Input))
+  }
+
+  /// Starts forwarding keys to the focused output's script, if it runs in a terminal.
+  fn interact(&mut self) -> Option<Action> {
+    let project = self.state.focused_output_project()?;
+    let running = self.state.projects[project]
+      .pty
+      .as_ref()
+      .is_some_and(Pty::is_running);
+    if !running {
+      let name = &self.state.projects[project].name;
+      return Some(Action::Error(format!("{name} has no running script")));
+    }
+    self.state.interact.project = Some(project);
+    self.state.active_component = ActiveComponent::Status;
+    self.state.timeline.visible = false;
+    self.state.problems.visible = false;
+    self.state.ports.visible = false;
+    Some(Action::EnterMode(AppMode::Interact))
+  }
+
+  fn stop_interacting(&mut self) -> Option<Action> {
+    self.state.interact.project.take()?;
+    Some(Action::EnterMode(self.input_mode()))
+  }
+
+  fn is_interact_escape(&self, key: KeyEvent) -> bool {
+    let escape = self.state.interact.escape;
+    escape.is_some_and(|escape| escape.code == key.code && escape.modifiers == key.modifiers)
+  }
+
+  fn handle_interact_key_event(&mut self, key: KeyEvent) -> Option<Action> {
+    if self.is_interact_escape(key) {
+      return self.stop_interacting();
+    }
+    let pty = self.state.interacting_pty(self.state.interact.project)?;
+    if let Err(err) = pty.send_key(key) {
+      self.stop_interacting();
+      return Some(Action::Error(err));
+    }
+    None
+  }
+
+  /// Opens a shell tab in the directory of `project`, or of the selected project.
+  fn open_shell(&mut self, project: Option<&str>) -> Option<Action> {
+    let index = match project {
+      Some(name) => match self.state.project_by_name(name) {
+        Some(index) => index,
+        None => return Some(Action::Error(format!("Unknown project `{name}`"))),
+      },
+      None => self.state.selected_project()?,
+    };
+    let project = &self.state.projects[index];
+    let dir = self.manager.base_path.join(&project.path);
+    match ShellTab::open(&project.name, &dir) {
+      Ok(tab) => {
+        self.state.shell.push(tab);
+        Some(Action::EnterMode(AppMode::Interact))
+      }
+      Err(err) => Some(Action::Error(err)),
+    }
+  }
+
+  fn toggle_shell(&mut self) -> Option<Action> {
+    if self.state.shell.visible {
+      self.state.shell.visible = false;
+      return Some(Action::EnterMode(self.input_mode()));
+    }
+    if self.state.shell.tabs.is_empty() {
+      return self.open_shell(None);
+    }
+    self.state.shell.visible = true;
+    Some(Action::EnterMode(AppMode::Interact))
+  }
+
+  fn handle_shell_key_event(&mut self, key: KeyEvent) -> Option<Action> {
+    if self.is_interact_escape(key) {
+      return self.toggle_shell();
+    }
+    match key.code {
+      KeyCode::PageUp if key.modifiers.contains(KeyModifiers::CONTROL) => {
+        self.state.shell.select_by(-1)
+      }
+      KeyCode::PageDown if key.modifiers.contains(KeyModifiers::CONTROL) => {
+        self.state.shell.select_by(1)
+      }
+      _ => {
+        let tab = self.state.shell.current()?;
+        if let Err(err) = tab.pty.send_key(key) {
+          return Some(Action::Error(err));
+        }
+      }
+    }
+    None
+  }
+
+  /// The app mode matching the Home mode once overlays are closed.
+  fn input_mode(&self) -> AppMode {
+    match self.state.mode {
+      Mode::Normal => AppMode::Home,
+      Mode::Search => AppMode::Input,
+    }
+  }
+
+  fn handle_palette_key_event(&mut self, key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let palette = &mut self.state.palette;
+    match key.code {
+      KeyCode::Esc => return self.toggle_palette(),
+      KeyCode::Enter => {
+        let action = palette.confirm();
+        if let Some(tx) = &self.command_tx {
+          let _ = tx.send(Action::EnterMode(self.input_mode()));
+        }
+        return action;
+      }
+      KeyCode::Down => palette.select_by(1),
+      KeyCode::Up => palette.select_by(-1),
+      KeyCode::Char('n') if ctrl => palette.select_by(1),
+      KeyCode::Char('p') if ctrl => palette.select_by(-1),
+      KeyCode::Backspace => {
+        let mut query = palette.query.clone();
+        query.pop();
+        palette.set_query(query);
+      }
+      KeyCode::Char(c) => {
+        let query = format!("{}{c}", palette.query);
+        palette.set_query(query);
+      }
+      _ => {}
+    }
+    None
+  }
+
+  /// Runs the actions of a command line in order, or reports why it can't be parsed.
+  fn run_command(&self, line: &str) -> Option<Action> {
+    match command::parse(line) {
+      Ok(actions) => {
+        if let Some(tx) = &self.command_tx {
+          for action in actions {
+            let _ = tx.send(action);
+          }
+        }
+        None
+      }
+      Err(err) => Some(Action::Error(err)),
+    }
+  }
+
+  fn handle_command_line_key_event(&mut self, key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let command_line = &mut self.state.command_line;
+    match key.code {
+      KeyCode::Esc => {
+        command_line.close();
+        return Some(Action::EnterMode(self.input_mode()));
+      }
+      KeyCode::Enter => {
+        let line = command_line.submit();
+        if let Some(tx) = &self.command_tx {
+          let _ = tx.send(Action::EnterMode(self.input_mode()));
+        }
+        return self.run_command(&line);
+      }
+      KeyCode::Tab => command_line.complete(&self.state.projects, true),
+      KeyCode::BackTab => command_line.complete(&self.state.projects, false),
+      KeyCode::Up => command_line.recall(true),
+      KeyCode::Down => command_line.recall(false),
+      KeyCode::Char('p') if ctrl => command_line.recall(true),
+      KeyCode::Char('n') if ctrl => command_line.recall(false),
+      KeyCode::Backspace => {
+        let mut input = command_line.input.clone();
+        if input.pop().is_none() {
+          // Like vim, deleting past the start leaves the command line.
+          command_line.close();
+          return Some(Action::EnterMode(self.input_mode()));
+        }
+        command_line.set_input(input);
+      }
+      KeyCode::Char(c) => {
+        let input = format!("{}{c}", command_line.input);
+        command_line.set_input(input);
+      }
+      _ => {}
+    }
+    None
+  }
+
+  /// The file location of the selected problem or, in an output view, of the current search
+  /// match or else the lowest visible line mentioning one.
+  fn location_to_open(&mut self) -> Option<FileLocation> {
+    let (project, (file, line, column)) = if self.state.problems.visible {
+      let (name, problem) = self.state.problems.selected()?;
+      let location = (problem.file.clone(), problem.line, problem.column);
+      (self.state.project_by_name(name)?, location)
+    } else if self.state.timeline.visible {
+      let view = &self.state.timeline.view;
+      self.state.timeline.with_lines(|lines| {
+        lines
+          .iter()
+          .skip(view.first_line(lines.len()))
+          .take(view.height)
+          .rev()
+          .find_map(|line| {
+            let location = editor::find_location(&line.text)?;
+            Some((self.state.project_by_name(&line.project)?, location))
+          })
+      })?
+    } else {
+      let project = self.state.focused_output_project()?;
+      let output = self.state.projects[project].output.lock().unwrap().clone();
+      let lines = self.state.log_search.visible_lines(&output);
+      let matches = self.state.log_search.matching_lines(&lines);
+      let location = match self.state.log_search.current {
+        Some(current) => matches
+          .get(current)
+          .and_then(|&index| editor::find_location(lines[index])),
+        None => {
+          let (view, len) = self.state.focused_log()?;
+          let rows = view.rows(&lines);
+          let first = view.first_line(len);
+          let shown = &rows[first..(first + view.height).min(len)];
+          let (first, last) = (shown.first()?.line, shown.last()?.line);
+          lines[first..=last]
+            .iter()
+            .rev()
+            .find_map(|line| editor::find_location(line))
+        }
+      };
+      (project, location?)
+    };
+    let project_dir = self
+      .manager
+      .base_path
+      .join(&self.state.projects[project].path);
+    Some(FileLocation::new(&project_dir, &file, line, column))
+  }
+
+  /// Scrolls the focused output view to the next or previous line matching the log search.
+  fn jump_to_match(&mut self, forward: bool) {
+    let Some(project) = self.state.focused_output_project() else {
+      return;
+    };
+    let output = self.state.projects[project].output.lock().unwrap().clone();
+    let lines = self.state.log_search.visible_lines(&output);
+    let Some((rows, from)) = self.state.focused_log().map(|(view, len)| {
+      let rows = view.rows(&lines);
+      let from = match rows.get(view.first_line(len)) {
+        Some(row) if !view.follow => row.line,
+        _ => lines.len(),
+      };
+      (rows, from)
+    }) else {
+      return;
+    };
+    if let Some(line) = self.state.log_search.jump(&lines, from, forward) {
+      if let Some((view, len)) = self.state.focused_log() {
+        view.scroll_to(len, log_view::first_row(&rows, line));
+      }
+    }
+  }
+
+  fn handle_log_prompt_key_event(&mut self, key: KeyEvent) -> Option<Action> {
+    let search = &mut self.state.log_search;
+    match key.code {
+      KeyCode::Esc => {
+        search.prompt = None;
+        return Some(Action::EnterMode(self.input_mode()));
+      }
+      KeyCode::Enter => {
+        let prompt = search.prompt;
+        let result = search.submit();
+        if let Some(tx) = &self.command_tx {
+          let _ = tx.send(Action::EnterMode(self.input_mode()));
+        }
+        if let Err(err) = result {
+          return Some(Action::Error(err));
+        }
+        if prompt == Some(LogPrompt::Search) {
+          self.jump_to_match(true);
+        }
+      }
+      KeyCode::Backspace => {
+        search.input.pop();
+      }
+      KeyCode::Char(c) => search.input.push(c),
+      _ => {}
+    }
+    None
+  }
+
+  fn handle_help_key_event(&mut self, key: KeyEvent) -> Option<Action> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    if ctrl && matches!(key.code, KeyCode::Char('p') | KeyCode::Char('n')) {
+      let previous = key.code == KeyCode::Char('p');
+      if let Some(query) = self.state.recall_search(SearchKind::Help, previous) {
+        self.state.help.query = query;
+        self.state.help.scroll = 0;
+      }
+      return None;
+    }
+    let help = &mut self.state.help;
+    match key.code {
+      KeyCode::Esc => return self.toggle_help(),
+      KeyCode::Down => help.scroll_by(1),
+      KeyCode::Up => help.scroll_by(-1),
+      KeyCode::PageDown => help.scroll_by(10),
+      KeyCode::PageUp => help.scroll_by(-10),
+      KeyCode::Backspace => {
+        help.query.pop();
+        help.scroll = 0;
+      }
+      KeyCode::Char(c) => {
+        help.query.push(c);
+        help.scroll = 0;
+      }
+      _ => {}
+    }
+    None
+  }
+
+  /// Runs `script`, watching it for automatic restarts; runs started by hand reset their
+  /// count.
+  fn run_script(&mut self, project_index: usize, script: &str, by_hand: bool) {
+    let project = &mut self.state.projects[project_index];
+    let rx: Receiver<String> = self.manager.execute_script(project, script);
+    if project.pty.as_ref().is_some_and(Pty::is_running) {
+      self
+        .state
+        .restarts
+        .entry(project_index)
+        .or_default()
+        .started(script, by_hand);
+    }
+    if let Some(pid) = self.state.projects[project_index].pid {
+      self.manager.sampler.track(pid);
+    }
+    self.forward_output(project_index, rx);
+  }
+
+  /// Marks projects whose process exited outside of texus, e.g. dev servers found running at
+  /// startup, as stopped. Scripts started here are handled by `supervise` once they exit.
+  fn process_exited(&mut self, pid: u32) {
+    for project in &mut self.state.projects {
+      if project.pid != Some(pid) {
+        continue;
+      }
+      project.pid = None;
+      if project.pty.is_none() && project.status == ProjectStatusKind::Running {
+        project.status = ProjectStatusKind::Stopped;
+        let message = format!("Process {pid} of {} exited", project.name);
+        self.state.timeline.timeline.push(&project.name, &message);
+        let mut output = project.output.lock().unwrap();
+        output.push_str(&message);
+        output.push('\n');
+      }
+    }
+  }
+
+  /// Adds the use of each running project's process tree to its history, and orders the list
+  /// by it when sorted.
+  fn record_usage(&mut self) {
+    let snapshot = self.manager.sampler.snapshot();
+    let mut usage = HashMap::new();
+    for project in &self.state.projects {
+      if let Some(sample) = project.pid.and_then(|pid| snapshot.tree_usage(pid)) {
+        let mut history = self.state.usage.remove(&project.name).unwrap_or_default();
+        history.push(sample);
+        usage.insert(project.name.clone(), history);
+      }
+    }
+    self.state.usage = usage;
+    if self.state.sort != ProjectSort::None {
+      self.state.resort_projects();
+    }
+  }
+
+  /// Lists the listening ports on a blocking task, which sends them as `PortsListed`.
+  fn refresh_ports(&mut self) {
+    let Some(tx) = self.command_tx.clone() else {
+      return;
+    };
+    if self.listing_ports {
+      return;
+    }
+    self.listing_ports = true;
+    let snapshot = self.manager.sampler.snapshot();
+    let projects = self.state.projects.clone();
+    let base_path = self.manager.base_path.clone();
+    tokio::task::spawn_blocking(move || {
+      let listening = ports::listening(&snapshot, &projects, &base_path);
+      let _ = tx.send(Action::PortsListed(listening));
+    });
+  }
+
+  /// Terminates the process listening on `port` in the ports listed last, so that a dev server
+  /// can take it. Projects owning it are not restarted.
+  fn kill_port(&mut self, port: u16) -> Option<Action> {
+    let Some(listening) = self.state.ports.find(port) else {
+      return Some(Action::Error(format!("Nothing listens on port {port}")));
+    };
+    let Some(pid) = listening.pid else {
+      return Some(Action::Error(format!(
+        "The owner of port {port} is unknown, it may belong to another user"
+      )));
+    };
+    let command = listening.command.clone().unwrap_or_default();
+    let project = listening
+      .project
+      .as_deref()
+      .and_then(|name| self.state.project_by_name(name));
+    if let Some(restart) = project.and_then(|index| self.state.restarts.get_mut(&index)) {
+      restart.stopped();
+    }
+    if let Err(err) = ProjectManager::terminate_process(pid) {
+      return Some(Action::Error(err));
+    }
+    Some(Action::Notify(format!(
+      "Killed {command} ({pid}) listening on port {port}"
+    )))
+  }
+
+  /// Appends the lines received from a script to the project's output and the timeline.
+  fn forward_output(&self, project_index: usize, rx: Receiver<String>) {
+    let project = &self.state.projects[project_index];
+    let project_output = Arc::clone(&project.output);
+    let project_name = project.name.clone();
+    let timeline = self.state.timeline.timeline.clone();
+
+    // Handle output in a separate thread
+    std::thread::spawn(move || {
+      while let Ok(message) = rx.recv() {
+        timeline.push(&project_name, &message);
+        let mut output = project_output.lock().unwrap();
+        output.push_str(&message);
+        output.push('\n');
+      }
+    });
+  }
+
+  /// Watches the projects with `"watch": true` to rebuild them and their dependents on changes.
+  fn start_watching(&mut self) {
+    self.watcher = None;
+    let roots: Vec<PathBuf> = self
+      .state
+      .projects
+      .iter()
+      .filter(|project| self.config.project(&project.name).watch)
+      .map(|project| self.manager.base_path.join(&project.path))
+      .collect();
+    if roots.is_empty() {
+      return;
+    }
+    let (ignore, mut errors) = IgnoreRules::new(&self.config.config.watch_ignore);
+    match ProjectWatcher::new(roots, ignore) {
+      Ok(watcher) => self.watcher = Some(watcher),
+      Err(err) => errors.push(format!("Not watching project files: {err}")),
+    }
+    if let Some(tx) = &self.command_tx {
+      for error in errors {
+        let _ = tx.send(Action::Error(error));
+      }
+    }
+  }
+
+  /// Rebuilds projects whose files changed and then their dependents, one at a time, cancelling
+  /// the rebuild running when its project is affected by newer changes.
+  fn rebuild(&mut self) {
+    let Some(watcher) = &mut self.watcher else {
+      return;
+    };
+    let now = Instant::now();
+    let projects = &self.state.projects;
+    let building = self.rebuild.as_ref().map(|rebuild| rebuild.project);
+    for path in watcher.changes() {
+      let Some(index) = projects
+        .iter()
+        .position(|project| path.starts_with(self.manager.base_path.join(&project.path)))
+      else {
+        continue;
+      };
+      // Builds write to their own project, which must not cancel and queue them again.
+      if building == Some(index) {
+        continue;
+      }
+      self.rebuilds.changed(index, now);
+      if let Some(rebuild) = self.rebuild.as_mut() {
+        if rebuild.exit().is_none()
+          && watch::build_order(projects, &[index]).contains(&rebuild.project)
+        {
+          rebuild.cancel();
+        }
+      }
+    }
+
+    if let Some(exit) = self.rebuild.as_ref().and_then(Rebuild::exit) {
+      let project = self
+        .rebuild
+        .take()
+        .map(|rebuild| rebuild.project)
+        .unwrap_or_default();
+      if !exit.success {
+        self.rebuilds.drop_dependents(projects, project);
+      }
+    }
+
+    let build = Build.to_string();
+    let debounce = Duration::from_millis(self.config.config.watch_debounce);
+    self.rebuilds.settle(projects, debounce, now, |index| {
+      projects[index].commands.contains(&build)
+    });
+    if self.rebuild.is_some() {
+      return;
+    }
+    let Some(index) = self.rebuilds.next() else {
+      return;
+    };
+    let project = &projects[index];
+    let dir = self.manager.base_path.join(&project.path);
+    match Rebuild::start(index, &project.name, &dir) {
+      Ok((rebuild, rx)) => {
+        self.rebuild = Some(rebuild);
+        self.forward_output(index, rx);
+      }
+      Err(err) => {
+        self.rebuilds.drop_dependents(projects, index);
+        if let Some(tx) = &self.command_tx {
+          let _ = tx.send(Action::Error(err));
+        }
+      }
+    }
+  }
+
+  /// Stops a project by hand, so that it is not restarted automatically.
+  fn stop_project(&mut self, project_index: usize) {
+    if let Some(restart) = self.state.restarts.get_mut(&project_index) {
+      restart.stopped();
+    }
+    self.manager.execute_command(
+      &mut self.state.projects[project_index],
+      &ProjectCommand::Stop,
+    );
+  }
+
+  /// Stops a project if it runs and starts its last script, or `start`, again.
+  fn restart_project(&mut self, project_index: usize) {
+    let script = self
+      .state
+      .restarts
+      .get(&project_index)
+      .and_then(|restart| restart.script.clone())
+      .unwrap_or_else(|| Start.to_string());
+    if self.state.projects[project_index].status == ProjectStatusKind::Running {
+      self.stop_project(project_index);
+    }
+    // The stopped run keeps its ports until it exits, so `supervise` starts the new one then.
+    let project = &self.state.projects[project_index];
+    if project.pty.as_ref().is_some_and(Pty::is_running) {
+      self
+        .state
+        .restarts
+        .entry(project_index)
+        .or_default()
+        .restart_after_exit(&script);
+      return;
+    }
+    self.run_script(project_index, &script, true);
+  }
+
+  /// Records the exits of scripts and restarts those whose policy and backoff say so.
+  fn supervise(&mut self) {
+    let now = Instant::now();
+    for index in 0..self.state.projects.len() {
+      let project = &mut self.state.projects[index];
+      let Some(exit) = project.pty.as_ref().and_then(Pty::exit) else {
+        continue;
+      };
+      if project.status == ProjectStatusKind::Running {
+        project.status = if exit.success {
+          ProjectStatusKind::Completed
+        } else {
+          ProjectStatusKind::Error
+        };
+      }
+      let config = self.config.project(&project.name);
+      let restart = self.state.restarts.entry(index).or_default();
+      if let Some(script) = restart.due_after_exit() {
+        self.run_script(index, &script, true);
+        continue;
+      }
+      restart.exited(exit, &config, now);
+      if let Some(script) = restart.due(now) {
+        let message = format!(
+          "Restarting {} ({}/{})",
+          project.name, restart.restarts, config.max_restarts
+        );
+        self.run_script(index, &script, false);
+        if let Some(tx) = &self.command_tx {
+          let _ = tx.send(Action::Notify(message));
+        }
+      }
+    }
+  }
+
+  fn recall_project_search(&mut self, previous: bool) {
+    if let Some(query) = self.state.recall_search(SearchKind::Projects, previous) {
+      self.state.search_query = query;
+      self.state.update_filtered_projects();
+    }
+  }
+
+  fn panel_at(&self, column: u16, row: u16) -> Option<ActiveComponent> {
+    let position = Position::new(column, row);
+    [
+      ActiveComponent::List,
+      ActiveComponent::Detail,
+      ActiveComponent::Status,
+    ]
+    .into_iter()
+    .zip(self.panel_areas)
+    .find(|(_, area)| area.contains(position))
+    .map(|(component, _)| component)
+  }
+
+  /// The border between two panels at `column`, which is two cells wide.
+  fn border_at(&self, column: u16) -> Option<usize> {
+    let resizable =
+      self.layout == LayoutPreset::Columns && self.panel_areas.iter().all(|area| !area.is_empty());
+    if !resizable {
+      return None;
+    }
+    self.panel_areas[1..]
+      .iter()
+      .position(|area| column + 1 == area.x || column == area.x)
+  }
+
+  /// Scrolls the focused panel by `halves` half pages of what it shows.
+  fn scroll_half_pages(&mut self, halves: i32) {
+    let component = self.state.active_component.clone();
+    let half_page = match component {
+      ActiveComponent::Status => self
+        .state
+        .focused_log()
+        .map_or(1, |(view, _)| view.half_page()),
+      _ => (self.panel_areas[component as usize]
+        .height
+        .saturating_sub(2)
+        / 2)
+        .max(1) as i32,
+    };
+    self.state.navigate(half_page * halves);
+  }
+
+  /// Focuses the pinned log under the mouse, so clicks and the wheel act on it.
+  fn focus_pane_at(&mut self, column: u16, row: u16) {
+    let position = Position::new(column, row);
+    let areas = LogGrid::pane_areas(self.panel_areas[2], self.state.log_panes.len());
+    if let Some(index) = areas.iter().position(|area| area.contains(position)) {
+      self.state.focused_pane = index;
+    }
+  }
+
+  fn resize_panels(&mut self, border: usize, column: u16) {
+    let left_edge = self.panel_areas[0].x;
+    let width = self.panel_areas.iter().map(|area| area.width).sum::<u16>();
+    if width == 0 {
+      return;
+    }
+    let percent = (column.saturating_sub(left_edge) as u32 * 100 / width as u32) as u16;
+    let widths = &mut self.panel_widths;
+    // Only the two panels next to the border change size.
+    let start: u16 = widths[..border].iter().sum();
+    let pair = widths[border] + widths[border + 1];
+    let left = percent
+      .saturating_sub(start)
+      .clamp(MIN_PANEL_WIDTH, pair - MIN_PANEL_WIDTH);
+    widths[border] = left;
+    widths[border + 1] = pair - left;
   }
 
   fn draw_block(&self, frame: &mut Frame, rect: Rect, title: &str, component: ActiveComponent) {
-    let active_border_style = Style::default().fg(Color::Rgb(126, 193, 14));
-    let inactive_border_style = Style::default().fg(Color::White);
+    let active_border_style = self.state.theme.style("border.active");
+    let inactive_border_style = self.state.theme.style("border.inactive");
 
     let block = Block::default()
       .borders(Borders::ALL)
@@ -161,43 +1515,256 @@ impl Component for Home {
   }
 
   fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    // `:theme` and `:layout` last until a reload changes the theme or the layout.
+    if config.config.theme != self.config.config.theme || config.styles != self.config.styles {
+      self.state.theme = Theme::from_config(&config);
+    }
+    if config.config.layout != self.config.config.layout {
+      self.layout = config.config.layout;
+    }
+    let escape = match parse_key_sequence(&config.config.interact_escape).as_deref() {
+      Ok([key]) => *key,
+      _ => {
+        if let Some(tx) = &self.command_tx {
+          let _ = tx.send(Action::Error(format!(
+            "Invalid `interact_escape` key `{}`, using `<ctrl-g>`",
+            config.config.interact_escape
+          )));
+        }
+        parse_key_sequence(&config::default_interact_escape()).unwrap()[0]
+      }
+    };
+    self.state.interact.escape = Some(escape);
+    let (highlights, errors) = log_search::compile_highlights(&config.config.highlights);
+    self.state.highlights = highlights;
+    if let Some(tx) = &self.command_tx {
+      for error in errors {
+        let _ = tx.send(Action::Error(error));
+      }
+    }
+    if let Some(tx) = &self.command_tx {
+      let interval = Duration::from_millis(config.config.process_sample_interval);
+      self.manager.sampler.start(interval, tx.clone());
+    }
     self.config = config;
+    self.start_watching();
     Ok(())
   }
 
   fn update(&mut self, action: Action) -> Result<Option<Action>> {
     match action {
-      Action::Tick => { /* Handle periodic updates */ }
+      Action::Tick => {
+        self.refresh_projects();
+        self.state.update_problems();
+        self.supervise();
+        self.rebuild();
+        let was_visible = self.state.shell.visible;
+        self.state.shell.remove_exited();
+        if was_visible && !self.state.shell.visible {
+          return Ok(Some(Action::EnterMode(self.input_mode())));
+        }
+        // Stop forwarding keys once the script exits.
+        let exited = self.state.interact.project.is_some_and(|project| {
+          !self.state.projects[project]
+            .pty
+            .as_ref()
+            .is_some_and(Pty::is_running)
+        });
+        if exited {
+          return Ok(self.stop_interacting());
+        }
+      }
+      Action::ProcessExited(pid) => self.process_exited(pid),
+      Action::ProcessesSampled => {
+        self.record_usage();
+        if self.state.ports.visible {
+          self.refresh_ports();
+        }
+      }
+      Action::TogglePorts => {
+        self.state.ports.visible = !self.state.ports.visible;
+        self.refresh_ports();
+      }
+      Action::PortsListed(listening) => {
+        self.listing_ports = false;
+        self.state.ports.update(listening);
+        if let Some(port) = self.kill_port_when_listed.take() {
+          return Ok(self.kill_port(port));
+        }
+      }
+      // List the ports first, so that the current owner is killed.
+      Action::KillPort(port) => {
+        self.kill_port_when_listed = Some(port);
+        self.refresh_ports();
+      }
+      Action::OpenPort(port) => {
+        if let Err(err) = ports::open_url(&ports::url(port)) {
+          return Ok(Some(Action::Error(err)));
+        }
+      }
+      Action::CopyPort(port) => {
+        let url = ports::url(port);
+        return Ok(Some(match ports::copy_to_clipboard(&url) {
+          Ok(()) => Action::Notify(format!("Copied {url}")),
+          Err(err) => Action::Error(err),
+        }));
+      }
+      Action::Interact => return Ok(self.interact()),
+      Action::ToggleShell => return Ok(self.toggle_shell()),
+      Action::OpenShell(project) => return Ok(self.open_shell(project.as_deref())),
       Action::Render => { /* Handle rendering logic */ }
-      Action::ProjectScript(cmd) => {
-        if let Some(&project_index) = self
+      Action::Help => return Ok(self.toggle_help()),
+      Action::CommandPalette => return Ok(self.toggle_palette()),
+      Action::SetTheme(name) => match Theme::new(&name, &self.config) {
+        Some(theme) => self.state.theme = theme,
+        None => {
+          let themes = Theme::names().collect::<Vec<_>>().join(", ");
+          return Ok(Some(Action::Error(format!(
+            "Unknown theme `{name}`, expected one of: {themes}"
+          ))));
+        }
+      },
+      Action::ProjectScript(script) => {
+        if let Some(project_index) = self.state.selected_project() {
+          self.run_script(project_index, &script.to_string(), true);
+        }
+      }
+      Action::RunScript(project, script) => match self.state.project_by_name(&project) {
+        Some(project_index) => self.run_script(project_index, &script, true),
+        None => return Ok(Some(Action::Error(format!("Unknown project `{project}`")))),
+      },
+      Action::StopProject(project) => match self.state.project_by_name(&project) {
+        Some(project_index) => self.stop_project(project_index),
+        None => return Ok(Some(Action::Error(format!("Unknown project `{project}`")))),
+      },
+      Action::Restart => {
+        if let Some(project_index) = self.state.selected_project() {
+          self.restart_project(project_index);
+        }
+      }
+      Action::RestartProject(project) => match self.state.project_by_name(&project) {
+        Some(project_index) => self.restart_project(project_index),
+        None => return Ok(Some(Action::Error(format!("Unknown project `{project}`")))),
+      },
+      Action::Filter(query) => {
+        self
           .state
-          .filtered_projects
-          .get(self.state.selected_project_index)
-        {
-          if let Some(project) = self.state.projects.get_mut(project_index) {
-            let rx: Receiver<String> = self.manager.execute_script(project, &cmd);
-            let project_output = Arc::clone(&project.output);
-
-            // Handle output in a separate thread
-            std::thread::spawn(move || {
-              while let Ok(message) = rx.recv() {
-                let mut output = project_output.lock().unwrap();
-                output.push_str(&message); // Append to the vector
-              }
-            });
+          .search_history
+          .entry(SearchKind::Projects)
+          .or_default()
+          .push(&query);
+        self.state.search_query = query;
+        self.state.update_filtered_projects();
+      }
+      Action::SortProjects(sort) => {
+        self.state.sort = sort;
+        self.state.resort_projects();
+      }
+      Action::SetOption(option, value) => match option.as_str() {
+        "follow" => {
+          if let Some((view, len)) = self.state.focused_log() {
+            let follow = value.unwrap_or(!view.follow);
+            view.set_follow(len, follow);
           }
         }
+        _ => return Ok(Some(Action::Error(format!("Unknown option `{option}`")))),
+      },
+      Action::SetLayout(layout) => {
+        self.layout = layout;
+        self.zoomed = false;
       }
-      Action::ProjectCommand(cmd) => {
-        if let Some(&project_index) = self
-          .state
-          .filtered_projects
-          .get(self.state.selected_project_index)
-        {
-          if let Some(project) = self.state.projects.get_mut(project_index) {
-            self.manager.execute_command(project, &cmd);
+      Action::ToggleZoom => self.zoomed = !self.zoomed,
+      Action::ScrollTop => self
+        .state
+        .scroll_to_end(self.state.active_component.clone(), true),
+      Action::ScrollBottom => self
+        .state
+        .scroll_to_end(self.state.active_component.clone(), false),
+      Action::ToggleTimeline => {
+        self.state.timeline.visible = !self.state.timeline.visible;
+        self.state.ports.visible = false;
+      }
+      Action::TimelineProjects(projects) => {
+        self.state.timeline.projects = projects;
+        self.state.timeline.visible = true;
+      }
+      Action::TimelineFilter(filter) => {
+        self.state.timeline.set_filter(filter);
+        self.state.timeline.visible = true;
+      }
+      Action::ToggleProblems => {
+        self.state.problems.visible = !self.state.problems.visible;
+        self.state.ports.visible = false;
+      }
+      Action::Command(line) => return Ok(self.run_command(&line)),
+      Action::ProjectCommand(ProjectCommand::StopAll) => {
+        for project_index in 0..self.state.projects.len() {
+          if self.state.projects[project_index].status == ProjectStatusKind::Running {
+            self.stop_project(project_index);
+          }
+        }
+      }
+      Action::ProjectCommand(ProjectCommand::Stop) => {
+        if let Some(project_index) = self.state.selected_project() {
+          self.stop_project(project_index);
+        }
+      }
+      _ => {}
+    }
+    Ok(None)
+  }
+
+  fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<Option<Action>> {
+    if self.state.shell.visible
+      || self.state.help.visible
+      || self.state.palette.visible
+      || self.state.command_line.visible
+    {
+      return Ok(None);
+    }
+    let (column, row) = (mouse.column, mouse.row);
+    match mouse.kind {
+      MouseEventKind::Down(MouseButton::Left) => {
+        if let Some(border) = self.border_at(column) {
+          self.dragged_border = Some(border);
+          return Ok(None);
+        }
+        let Some(component) = self.panel_at(column, row) else {
+          return Ok(None);
+        };
+        self.state.active_component = component.clone();
+        let area = self.panel_areas[component.clone() as usize];
+        self.focus_pane_at(column, row);
+        match component {
+          ActiveComponent::List if row > area.y && row + 1 < area.bottom() => {
+            let index = ProjectList::first_visible(&self.state, area) + (row - area.y - 1) as usize;
+            if index < self.state.filtered_projects.len() {
+              self.state.selected_project_index = index;
+            }
           }
+          ActiveComponent::Detail if row + 1 == area.bottom() => {
+            return Ok(ProjectDetail::hint_at(area, column));
+          }
+          _ => {}
+        }
+      }
+      MouseEventKind::Drag(MouseButton::Left) => {
+        if let Some(border) = self.dragged_border {
+          self.resize_panels(border, column);
+        }
+      }
+      MouseEventKind::Up(MouseButton::Left) => self.dragged_border = None,
+      MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+        let direction = if mouse.kind == MouseEventKind::ScrollDown {
+          1
+        } else {
+          -1
+        };
+        self.focus_pane_at(column, row);
+        match self.panel_at(column, row) {
+          Some(ActiveComponent::List) => self.state.scroll(ActiveComponent::List, direction),
+          Some(component) => self.state.scroll(component, direction * WHEEL_STEP),
+          None => {}
         }
       }
       _ => {}
@@ -206,21 +1773,128 @@ impl Component for Home {
   }
 
   fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+    if self.state.shell.visible {
+      return Ok(self.handle_shell_key_event(key));
+    }
+    if self.state.interact.project.is_some() {
+      return Ok(self.handle_interact_key_event(key));
+    }
+    if self.state.palette.visible {
+      return Ok(self.handle_palette_key_event(key));
+    }
+    if self.state.help.visible {
+      return Ok(self.handle_help_key_event(key));
+    }
+    if self.state.command_line.visible {
+      return Ok(self.handle_command_line_key_event(key));
+    }
+    if self.state.log_search.prompt.is_some() {
+      return Ok(self.handle_log_prompt_key_event(key));
+    }
+    let searches_output = self.state.active_component == ActiveComponent::Status
+      && !self.state.timeline.visible
+      && !self.state.problems.visible
+      && !self.state.ports.visible;
     match self.state.mode {
       Mode::Normal => match key.code {
-        KeyCode::Char('/') => self.state.toggle_search_mode(),
+        KeyCode::Char('?') => return Ok(Some(Action::Help)),
+        KeyCode::Char('/') if searches_output => {
+          self.state.log_search.open(LogPrompt::Search);
+          return Ok(Some(Action::EnterMode(AppMode::Input)));
+        }
+        KeyCode::Char('&') if searches_output => {
+          self.state.log_search.open(LogPrompt::Filter);
+          return Ok(Some(Action::EnterMode(AppMode::Input)));
+        }
+        KeyCode::Char('n') if searches_output => self.jump_to_match(true),
+        KeyCode::Char('N') if searches_output => self.jump_to_match(false),
+        KeyCode::Char('/') => {
+          self.state.toggle_search_mode();
+          return Ok(Some(Action::EnterMode(AppMode::Input)));
+        }
+        KeyCode::Char(':') => {
+          self.state.command_line.open();
+          return Ok(Some(Action::EnterMode(AppMode::Input)));
+        }
         KeyCode::Char('j') | KeyCode::Down => self.state.navigate(1),
         KeyCode::Char('k') | KeyCode::Up => self.state.navigate(-1),
         KeyCode::Char('l') | KeyCode::Right => self.state.switch_active_component(true),
         KeyCode::Char('h') | KeyCode::Left => self.state.switch_active_component(false),
+        // `<ctrl-p>` opens the command palette.
+        KeyCode::Char('p') if key.modifiers == KeyModifiers::NONE => {
+          if let Err(err) = self.state.toggle_pin() {
+            return Ok(Some(Action::Error(err)));
+          }
+        }
+        KeyCode::Tab => self.state.focus_pane(true),
+        KeyCode::BackTab => self.state.focus_pane(false),
+        KeyCode::Char('t') => return Ok(Some(Action::ToggleTimeline)),
+        KeyCode::Char('e') => return Ok(Some(Action::ToggleProblems)),
+        KeyCode::Char('P') => return Ok(Some(Action::TogglePorts)),
+        KeyCode::Char('x' | 'o' | 'y') if self.state.ports.visible => {
+          let Some(port) = self.state.ports.selected().map(|port| port.port) else {
+            return Ok(Some(Action::Error("No port selected".to_string())));
+          };
+          return Ok(Some(match key.code {
+            KeyCode::Char('x') => Action::KillPort(port),
+            KeyCode::Char('o') => Action::OpenPort(port),
+            _ => Action::CopyPort(port),
+          }));
+        }
+        KeyCode::Char('i') => return Ok(Some(Action::Interact)),
+        KeyCode::Char('`') => return Ok(Some(Action::ToggleShell)),
+        KeyCode::Char('T') => return Ok(Some(Action::OpenShell(None))),
+        KeyCode::Char('o') => match self.location_to_open() {
+          Some(location) => return Ok(Some(Action::OpenFile(location))),
+          None => return Ok(Some(Action::Error("No file location to open".to_string()))),
+        },
+        KeyCode::Char('m') => {
+          if let Some(project) = self.state.get_selected_project() {
+            let name = project.name.clone();
+            self.state.timeline.toggle_project(&name);
+            self.state.timeline.visible = true;
+          }
+        }
+        KeyCode::Char('f') => {
+          if let Some((view, len)) = self.state.focused_log() {
+            let follow = !view.follow;
+            view.set_follow(len, follow);
+          }
+        }
+        KeyCode::PageDown => self.scroll_half_pages(2),
+        KeyCode::PageUp => self.scroll_half_pages(-2),
+        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          self.scroll_half_pages(1)
+        }
+        KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          self.scroll_half_pages(-1)
+        }
         KeyCode::Char('s') => return Ok(Some(Action::ProjectScript(Start))),
         KeyCode::Char('b') => return Ok(Some(Action::ProjectScript(Build))),
         //TODO: Implement close and close all project
         KeyCode::Char('c') => return Ok(Some(Action::ProjectCommand(ProjectCommand::Stop))),
+        KeyCode::Char('r') => return Ok(Some(Action::Restart)),
         _ => {}
       },
       Mode::Search => match key.code {
-        KeyCode::Esc => self.state.toggle_search_mode(),
+        KeyCode::Esc | KeyCode::Enter => {
+          self
+            .state
+            .search_history
+            .entry(SearchKind::Projects)
+            .or_default()
+            .push(&self.state.search_query);
+          self.state.toggle_search_mode();
+          return Ok(Some(Action::EnterMode(AppMode::Home)));
+        }
+        KeyCode::Up => self.recall_project_search(true),
+        KeyCode::Down => self.recall_project_search(false),
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          self.recall_project_search(true)
+        }
+        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+          self.recall_project_search(false)
+        }
         KeyCode::Char(c) => {
           self.state.search_query.push(c);
           self.state.update_filtered_projects();
@@ -236,51 +1910,120 @@ impl Component for Home {
   }
 
   fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
-    if !self.state.logo.is_rendered && !self.state.projects.is_empty() {
+    frame.render_widget(Block::default().style(self.state.theme.style("base")), area);
+
+    if self.state.shell.visible {
+      ShellView::draw(&self.state, frame, area);
+      return Ok(());
+    }
+
+    self.panel_areas = layout::panel_areas(
+      self.layout,
+      self.panel_widths,
+      area,
+      self.state.active_component.clone() as usize,
+      self.zoomed,
+      self.config.config.collapse_width,
+    );
+    let [list_area, detail_area, status_area] = self.panel_areas;
+
+    if !list_area.is_empty() {
+      self.draw_block(frame, list_area, "Project List", ActiveComponent::List);
+      ProjectList::draw(&self.state, frame, list_area);
+    }
+    if !detail_area.is_empty() {
+      self.draw_block(
+        frame,
+        detail_area,
+        "Project Detail",
+        ActiveComponent::Detail,
+      );
+      ProjectDetail::draw(&self.state, frame, detail_area);
+    }
+    if !status_area.is_empty() {
+      self.draw_block(
+        frame,
+        status_area,
+        "Project Status",
+        ActiveComponent::Status,
+      );
+      // Views scroll by what fits in them, so remember their sizes.
+      self.state.timeline.view.height = status_area.height.saturating_sub(2) as usize;
+      self.state.log_view.height = status_area
+        .height
+        .saturating_sub(ProjectStatus::header_height(&self.state) + 2)
+        as usize;
+      self.state.log_view.width = status_area.width.saturating_sub(2) as usize;
+      let pane_areas = LogGrid::pane_areas(status_area, self.state.log_panes.len());
+      for (pane, pane_area) in self.state.log_panes.iter_mut().zip(pane_areas) {
+        pane.view.height = pane_area.height.saturating_sub(2) as usize;
+        pane.view.width = pane_area.width.saturating_sub(2) as usize;
+      }
+      if self.state.ports.visible {
+        PortsView::draw(&self.state, frame, status_area);
+      } else if self.state.problems.visible {
+        ProblemsView::draw(&self.state, frame, status_area);
+      } else if self.state.timeline.visible {
+        TimelineView::draw(&self.state, frame, status_area);
+      } else if self.state.log_panes.is_empty() {
+        ProjectStatus::draw(&self.state, frame, status_area);
+      } else {
+        LogGrid::draw(&self.state, frame, status_area);
+      }
+    }
+
+    // The splash shows the progress of the first scan over the projects streaming in.
+    if !self.state.logo.is_rendered {
       let area = frame.area();
       let (logo_width, logo_height) = self.state.logo.get_size();
       if logo_width < area.width && logo_height < area.height {
-        frame.render_widget(
-          &self.state.logo,
-          Rect::new(
-            area.width / 2 - logo_width / 2,
-            area.height / 2 - logo_height / 2,
-            logo_width,
-            logo_height,
-          ),
+        let logo_area = Rect::new(
+          area.width / 2 - logo_width / 2,
+          area.height / 2 - logo_height / 2,
+          logo_width,
+          logo_height,
         );
-        self.state.logo.is_rendered = self.state.logo.init_time.elapsed().as_millis() > 500;
+        frame.render_widget(Clear, logo_area);
+        self.state.logo.style = self.state.theme.style("logo");
+        frame.render_widget(&self.state.logo, logo_area);
       }
+      self.state.logo.is_rendered =
+        self.scan.is_none() && self.state.logo.init_time.elapsed().as_millis() > 500;
     }
 
-    let rects = Layout::default()
-      .direction(Direction::Horizontal)
-      .constraints(
-        [
-          Constraint::Percentage(25),
-          Constraint::Percentage(30),
-          Constraint::Percentage(45),
-        ]
-        .as_ref(),
-      )
-      .split(area);
-
-    // Draw ProjectList
-    self.draw_block(frame, rects[0], "Project List", ActiveComponent::List);
-
-    // Draw ProjectDetail
-    self.draw_block(frame, rects[1], "Project Detail", ActiveComponent::Detail);
-
-    // Draw ProjectStatus
-    self.draw_block(frame, rects[2], "Project Status", ActiveComponent::Status);
-
-    // tracing::info!(
-    //   "cihat cihats: {:?}",
-    //   self.state.get_selected_project().output
-    // );
-    ProjectList::draw(&self.state, frame, rects[0]);
-    ProjectDetail::draw(&self.state, frame, rects[1]);
-    ProjectStatus::draw(&self.state, frame, rects[2]);
+    if self.state.command_line.visible {
+      CommandLine::draw(&self.state, frame, area);
+    }
+    if self.state.help.visible {
+      Help::draw(&self.state, frame, area);
+    }
+    if self.state.palette.visible {
+      Palette::draw(&self.state, frame, area);
+    }
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn test_ctrl_p_does_not_pin() {
+    std::env::set_var("TEXUS_MONOREPO_PATH", std::env::temp_dir());
+    let mut home = Home::default();
+    home.state.projects = vec![Project {
+      name: "web-app".to_string(),
+      ..Default::default()
+    }];
+    home.state.update_filtered_projects();
+
+    let ctrl_p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL);
+    home.handle_key_event(ctrl_p).unwrap();
+    assert!(home.state.log_panes.is_empty());
+
+    let p = KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE);
+    home.handle_key_event(p).unwrap();
+    assert_eq!(home.state.log_panes.len(), 1);
+  }
+}
@@ -4,7 +4,7 @@ use ansi_to_tui::IntoText;
 use ratatui::{
   buffer::Buffer,
   layout::Rect,
-  style::{Color, Style, Stylize},
+  style::{Style, Stylize},
   text::Text,
   widgets::{Widget, WidgetRef},
 };
@@ -33,11 +33,17 @@ const LOGO: &str = "
 
 const WIDTH: u16 = 48;
 const HEIGHT: u16 = 28;
+/// Width of the progress bar below the message.
+const BAR_WIDTH: u16 = 24;
 
 #[derive(Debug)]
 pub struct Logo {
   pub init_time: Instant,
   pub is_rendered: bool,
+  /// Directories of the workspace read so far, and in total once known.
+  pub progress: (usize, usize),
+  /// The `logo` theme style of the message and progress bar.
+  pub style: Style,
 }
 
 impl Default for Logo {
@@ -45,6 +51,8 @@ impl Default for Logo {
     Self {
       init_time: Instant::now(),
       is_rendered: false,
+      progress: (0, 0),
+      style: Style::default(),
     }
   }
 }
@@ -53,19 +61,38 @@ impl WidgetRef for Logo {
   fn render_ref(&self, area: Rect, buf: &mut Buffer) {
     let text: Text = LOGO.into_text().expect("failed to parse ANSI");
     text.render(area, buf);
-    let message = "Loading...";
+    let style = self.style.italic();
+    let (scanned, total) = self.progress;
+    let message = match total {
+      0 => "Loading...".to_string(),
+      total => format!("Scanning projects {scanned}/{total}"),
+    };
     buf.set_string(
-      WIDTH / 2 - message.len() as u16 / 2 + area.x,
+      WIDTH / 2 - message.chars().count() as u16 / 2 + area.x,
       HEIGHT + area.y,
       message,
-      Style::default().fg(Color::Rgb(248, 190, 117)).italic(),
-    )
+      style,
+    );
+    if let Some(filled) = (BAR_WIDTH as usize * scanned.min(total)).checked_div(total) {
+      let filled = filled as u16;
+      let bar = format!(
+        "{}{}",
+        "█".repeat(filled as usize),
+        "░".repeat((BAR_WIDTH - filled) as usize)
+      );
+      buf.set_string(
+        WIDTH / 2 - BAR_WIDTH / 2 + area.x,
+        HEIGHT + 1 + area.y,
+        bar,
+        style.not_italic(),
+      );
+    }
   }
 }
 
 impl Logo {
-  /// Returns the size of the logo.
+  /// Returns the size of the logo with the message and progress bar below it.
   pub fn get_size(&self) -> (u16, u16) {
-    (WIDTH, HEIGHT)
+    (WIDTH, HEIGHT + 2)
   }
 }
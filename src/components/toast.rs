@@ -0,0 +1,111 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::Result;
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::Component;
+
+use crate::{action::Action, config::Config, theme::Theme};
+
+const MAX_VISIBLE: usize = 3;
+const WIDTH: u16 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+  Info,
+  Error,
+}
+
+#[derive(Debug)]
+struct Message {
+  level: Level,
+  text: String,
+  expires_at: Instant,
+}
+
+/// Short-lived notifications for `Action::Notify` and `Action::Error`.
+#[derive(Default)]
+pub struct Toast {
+  config: Config,
+  theme: Theme,
+  messages: Vec<Message>,
+}
+
+impl Toast {
+  fn push(&mut self, level: Level, text: String) {
+    let lifetime = match level {
+      Level::Info => Duration::from_secs(4),
+      Level::Error => Duration::from_secs(8),
+    };
+    self.messages.push(Message {
+      level,
+      text,
+      expires_at: Instant::now() + lifetime,
+    });
+  }
+}
+
+impl Component for Toast {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.theme = Theme::from_config(&config);
+    self.config = config;
+    Ok(())
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::Notify(text) => self.push(Level::Info, text),
+      Action::Error(text) => self.push(Level::Error, text),
+      Action::SetTheme(name) => {
+        if let Some(theme) = Theme::new(&name, &self.config) {
+          self.theme = theme;
+        }
+      }
+      Action::Tick => {
+        let now = Instant::now();
+        self.messages.retain(|message| message.expires_at > now);
+      }
+      _ => {}
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+    let width = WIDTH.min(area.width);
+    // Leave the first row to the FPS counter.
+    let mut y = area.y + 1;
+    for message in self.messages.iter().rev().take(MAX_VISIBLE) {
+      let text_width = width.saturating_sub(2).max(1) as usize;
+      let lines = message
+        .text
+        .lines()
+        .map(|line| line.chars().count().div_ceil(text_width).max(1))
+        .sum::<usize>() as u16;
+      let height = lines + 2;
+      if y + height > area.y + area.height {
+        break;
+      }
+      let (title, style) = match message.level {
+        Level::Info => ("Info", self.theme.style("toast.info")),
+        Level::Error => ("Error", self.theme.style("toast.error")),
+      };
+      let popup = Rect::new(area.x + area.width - width, y, width, height);
+      let paragraph = Paragraph::new(message.text.clone())
+        .wrap(Wrap { trim: false })
+        .block(
+          Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_style(style)
+            .style(self.theme.style("base")),
+        );
+      frame.render_widget(Clear, popup);
+      frame.render_widget(paragraph, popup);
+      y += height;
+    }
+    Ok(())
+  }
+}
@@ -0,0 +1,124 @@
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::Component;
+
+use crate::{
+  action::Action,
+  app::Mode,
+  config::{key_event_to_string, Config},
+  theme::Theme,
+};
+
+/// Popup listing the possible completions of a pending multi-key binding.
+#[derive(Default)]
+pub struct WhichKey {
+  config: Config,
+  theme: Theme,
+  mode: Mode,
+  pending: Vec<KeyEvent>,
+}
+
+impl WhichKey {
+  fn completions(&self) -> Vec<(String, String)> {
+    let Some(keymap) = self.config.keybindings.get(&self.mode) else {
+      return Vec::new();
+    };
+    let mut completions: Vec<(String, String)> = keymap
+      .iter()
+      .filter(|(keys, _)| keys.len() > self.pending.len() && keys.starts_with(&self.pending))
+      .map(|(keys, action)| {
+        let remaining = keys[self.pending.len()..]
+          .iter()
+          .map(|key| format!("<{}>", key_event_to_string(key)))
+          .collect();
+        (remaining, format!("{action:?}"))
+      })
+      .collect();
+    completions.sort();
+    completions
+  }
+}
+
+impl Component for WhichKey {
+  fn register_config_handler(&mut self, config: Config) -> Result<()> {
+    self.theme = Theme::from_config(&config);
+    self.config = config;
+    Ok(())
+  }
+
+  fn update(&mut self, action: Action) -> Result<Option<Action>> {
+    match action {
+      Action::PendingKeys(keys) => self.pending = keys,
+      Action::SetTheme(name) => {
+        if let Some(theme) = Theme::new(&name, &self.config) {
+          self.theme = theme;
+        }
+      }
+      Action::EnterMode(mode) => {
+        self.mode = mode;
+        self.pending.clear();
+      }
+      _ => {}
+    }
+    Ok(None)
+  }
+
+  fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+    if self.pending.is_empty() {
+      return Ok(());
+    }
+    let completions = self.completions();
+    let key_width = completions
+      .iter()
+      .map(|(keys, _)| keys.chars().count())
+      .max()
+      .unwrap_or_default();
+    let lines: Vec<Line> = completions
+      .into_iter()
+      .map(|(keys, action)| {
+        Line::from(vec![
+          Span::styled(format!("{keys:<key_width$}"), self.theme.style("key")),
+          Span::raw(" → "),
+          Span::raw(action),
+        ])
+      })
+      .collect();
+
+    let pending: String = self
+      .pending
+      .iter()
+      .map(|key| format!("<{}>", key_event_to_string(key)))
+      .collect();
+    let width = lines
+      .iter()
+      .map(|line| line.width() as u16)
+      .chain([pending.chars().count() as u16])
+      .max()
+      .unwrap_or_default()
+      .saturating_add(4)
+      .min(area.width);
+    let height = (lines.len() as u16).saturating_add(2).min(area.height);
+    let popup = Rect::new(
+      area.x + area.width - width,
+      area.y + area.height - height,
+      width,
+      height,
+    );
+
+    let paragraph = Paragraph::new(lines).block(
+      Block::default()
+        .title(pending)
+        .borders(Borders::ALL)
+        .border_style(self.theme.style("popup.border"))
+        .style(self.theme.style("base")),
+    );
+    frame.render_widget(Clear, popup);
+    frame.render_widget(paragraph, popup);
+    Ok(())
+  }
+}
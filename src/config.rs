@@ -7,20 +7,198 @@ use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use derive_deref::{Deref, DerefMut};
 use directories::ProjectDirs;
 use lazy_static::lazy_static;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use ratatui::style::{Color, Modifier, Style};
 use serde::{de::Deserializer, Deserialize};
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::error;
 
-use crate::{action::Action, app::Mode};
+use crate::{action::Action, app::Mode, layout::LayoutPreset, restart::RestartPolicy, theme};
 
 const CONFIG: &str = include_str!("../.config/config.json5");
 
-#[derive(Clone, Debug, Deserialize, Default)]
+/// Files in the config directory that are merged into the configuration, in load order.
+pub const CONFIG_FILES: [(&str, config::FileFormat); 5] = [
+  ("config.json5", config::FileFormat::Json5),
+  ("config.json", config::FileFormat::Json),
+  ("config.yaml", config::FileFormat::Yaml),
+  ("config.toml", config::FileFormat::Toml),
+  ("config.ini", config::FileFormat::Ini),
+];
+
+#[derive(Clone, Debug, Deserialize)]
 pub struct AppConfig {
   #[serde(default)]
   pub data_dir: PathBuf,
   #[serde(default)]
   pub config_dir: PathBuf,
+  /// Milliseconds to wait for the next key of a multi-key binding.
+  #[serde(default = "default_chord_timeout")]
+  pub chord_timeout: u64,
+  /// Name of the built-in theme the `styles` overrides are layered on.
+  #[serde(default = "default_theme")]
+  pub theme: String,
+  /// Arrangement of the panels.
+  #[serde(default)]
+  pub layout: LayoutPreset,
+  /// Terminal width in columns below which only the focused panel is shown.
+  #[serde(default = "default_collapse_width")]
+  pub collapse_width: u16,
+  /// Styles applied to output text matching a regex, in order.
+  #[serde(default = "default_highlights")]
+  pub highlights: Vec<HighlightConfig>,
+  /// Editor to open files in, overriding `$VISUAL` and `$EDITOR`.
+  #[serde(default)]
+  pub editor: Option<String>,
+  /// Command templates by editor name, layered on the built-in ones.
+  #[serde(default)]
+  pub editors: HashMap<String, EditorConfig>,
+  /// Key that stops forwarding keys to a script, e.g. `<ctrl-g>`.
+  #[serde(default = "default_interact_escape")]
+  pub interact_escape: String,
+  /// Settings of individual projects by name.
+  #[serde(default)]
+  pub projects: HashMap<String, ProjectConfig>,
+  /// Milliseconds watched files must stay unchanged before rebuilding.
+  #[serde(default = "default_watch_debounce")]
+  pub watch_debounce: u64,
+  /// Globs of paths the file watcher ignores, matched against each path component and the
+  /// path relative to the project.
+  #[serde(default = "default_watch_ignore")]
+  pub watch_ignore: Vec<String>,
+  /// Milliseconds between samples of the running processes.
+  #[serde(default = "default_process_sample_interval")]
+  pub process_sample_interval: u64,
+}
+
+/// A highlight rule as written in the config, e.g. `{ "pattern": "warn", "style": "yellow" }`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct HighlightConfig {
+  pub pattern: String,
+  pub style: String,
+}
+
+/// How to open a file in an editor, e.g. `{ "command": "subl {file}:{line}:{column}" }`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct EditorConfig {
+  /// `{file}`, `{line}` and `{column}` are replaced by the location to open.
+  pub command: String,
+  /// Whether the editor runs in the terminal, suspending texus until it exits.
+  #[serde(default = "default_terminal")]
+  pub terminal: bool,
+}
+
+fn default_terminal() -> bool {
+  true
+}
+
+fn default_chord_timeout() -> u64 {
+  1000
+}
+
+fn default_theme() -> String {
+  theme::DEFAULT_THEME.to_string()
+}
+
+fn default_collapse_width() -> u16 {
+  80
+}
+
+/// Settings of one project, e.g. `{ "restart_policy": "on-failure", "max_restarts": 3 }`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProjectConfig {
+  #[serde(default)]
+  pub restart_policy: RestartPolicy,
+  /// Most automatic restarts before giving up, counted from the last start by hand.
+  #[serde(default = "default_max_restarts")]
+  pub max_restarts: u32,
+  /// Milliseconds before the first automatic restart, doubling with every further one.
+  #[serde(default = "default_restart_delay")]
+  pub restart_delay: u64,
+  /// Whether to rebuild the project when its files, or those of its dependencies, change.
+  #[serde(default)]
+  pub watch: bool,
+}
+
+impl Default for ProjectConfig {
+  fn default() -> Self {
+    Self {
+      restart_policy: RestartPolicy::default(),
+      max_restarts: default_max_restarts(),
+      restart_delay: default_restart_delay(),
+      watch: false,
+    }
+  }
+}
+
+fn default_max_restarts() -> u32 {
+  5
+}
+
+fn default_restart_delay() -> u64 {
+  1000
+}
+
+fn default_process_sample_interval() -> u64 {
+  1000
+}
+
+fn default_watch_debounce() -> u64 {
+  300
+}
+
+/// Dependencies, VCS data and build output, which builds would otherwise rebuild on.
+/// `build` and `lib` are only ignored at the project root, where tools write them.
+fn default_watch_ignore() -> Vec<String> {
+  [
+    "node_modules",
+    "dist",
+    ".git",
+    "*.tsbuildinfo",
+    "build/**",
+    "lib/**",
+    ".next",
+    ".turbo",
+    "coverage",
+    "storybook-static",
+  ]
+  .map(str::to_string)
+  .to_vec()
+}
+
+pub fn default_interact_escape() -> String {
+  "<ctrl-g>".to_string()
+}
+
+fn default_highlights() -> Vec<HighlightConfig> {
+  [("(?i)error|ERR!", "bold red"), ("(?i)warn", "yellow")]
+    .into_iter()
+    .map(|(pattern, style)| HighlightConfig {
+      pattern: pattern.to_string(),
+      style: style.to_string(),
+    })
+    .collect()
+}
+
+impl Default for AppConfig {
+  fn default() -> Self {
+    Self {
+      data_dir: PathBuf::default(),
+      config_dir: PathBuf::default(),
+      chord_timeout: default_chord_timeout(),
+      theme: default_theme(),
+      layout: LayoutPreset::default(),
+      collapse_width: default_collapse_width(),
+      highlights: default_highlights(),
+      editor: None,
+      editors: HashMap::new(),
+      interact_escape: default_interact_escape(),
+      projects: HashMap::new(),
+      watch_debounce: default_watch_debounce(),
+      watch_ignore: default_watch_ignore(),
+      process_sample_interval: default_process_sample_interval(),
+    }
+  }
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -45,6 +223,16 @@ lazy_static! {
 }
 
 impl Config {
+  /// The settings of the named project; the `config` crate lowercases the names.
+  pub fn project(&self, name: &str) -> ProjectConfig {
+    self
+      .config
+      .projects
+      .get(&name.to_lowercase())
+      .cloned()
+      .unwrap_or_default()
+  }
+
   pub fn new() -> Result<Self, config::ConfigError> {
     let default_config: Config = json5::from_str(CONFIG).unwrap();
     let data_dir = get_data_dir();
@@ -53,15 +241,8 @@ impl Config {
       .set_default("data_dir", data_dir.to_str().unwrap())?
       .set_default("config_dir", config_dir.to_str().unwrap())?;
 
-    let config_files = [
-      ("config.json5", config::FileFormat::Json5),
-      ("config.json", config::FileFormat::Json),
-      ("config.yaml", config::FileFormat::Yaml),
-      ("config.toml", config::FileFormat::Toml),
-      ("config.ini", config::FileFormat::Ini),
-    ];
     let mut found_config = false;
-    for (file, format) in &config_files {
+    for (file, format) in &CONFIG_FILES {
       let source = config::File::from(config_dir.join(file))
         .format(*format)
         .required(false);
@@ -95,6 +276,26 @@ impl Config {
   }
 }
 
+/// Watches the config directory and requests a reload whenever one of `CONFIG_FILES` changes.
+pub fn watch(action_tx: UnboundedSender<Action>) -> notify::Result<RecommendedWatcher> {
+  let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+    let Ok(event) = event else {
+      return;
+    };
+    let is_config_file = event.paths.iter().any(|path| {
+      path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| CONFIG_FILES.iter().any(|(file, _)| *file == name))
+    });
+    if is_config_file && !event.kind.is_access() {
+      let _ = action_tx.send(Action::ReloadConfig);
+    }
+  })?;
+  watcher.watch(&get_config_dir(), RecursiveMode::NonRecursive)?;
+  Ok(watcher)
+}
+
 pub fn get_data_dir() -> PathBuf {
   let directory = if let Some(s) = DATA_FOLDER.clone() {
     s
@@ -136,11 +337,12 @@ impl<'de> Deserialize<'de> for KeyBindings {
       .map(|(mode, inner_map)| {
         let converted_inner_map = inner_map
           .into_iter()
-          .map(|(key_str, cmd)| (parse_key_sequence(&key_str).unwrap(), cmd))
-          .collect();
-        (mode, converted_inner_map)
+          .map(|(key_str, cmd)| Ok((parse_key_sequence(&key_str)?, cmd)))
+          .collect::<Result<_, String>>()?;
+        Ok((mode, converted_inner_map))
       })
-      .collect();
+      .collect::<Result<_, String>>()
+      .map_err(serde::de::Error::custom)?;
 
     Ok(KeyBindings(keybindings))
   }
@@ -317,7 +519,7 @@ pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
   sequences.into_iter().map(parse_key_event).collect()
 }
 
-#[derive(Clone, Debug, Default, Deref, DerefMut)]
+#[derive(Clone, Debug, Default, PartialEq, Deref, DerefMut)]
 pub struct Styles(pub HashMap<Mode, HashMap<String, Style>>);
 
 impl<'de> Deserialize<'de> for Styles {
@@ -343,10 +545,9 @@ impl<'de> Deserialize<'de> for Styles {
 }
 
 pub fn parse_style(line: &str) -> Style {
-  let (foreground, background) =
-    line.split_at(line.to_lowercase().find("on ").unwrap_or(line.len()));
+  let (foreground, background) = split_background(line);
   let foreground = process_color_string(foreground);
-  let background = process_color_string(&background.replace("on ", ""));
+  let background = process_color_string(background);
 
   let mut style = Style::default();
   if let Some(fg) = parse_color(&foreground.0) {
@@ -359,6 +560,39 @@ pub fn parse_style(line: &str) -> Style {
   style
 }
 
+/// Checks that every word of a style string is a modifier or a color `parse_style` understands.
+pub fn validate_style(line: &str) -> Result<(), String> {
+  let (foreground, background) = split_background(line);
+  for part in [foreground, background] {
+    let color = part
+      .split_whitespace()
+      .filter(|word| !["bold", "underline", "inverse", "bright"].contains(word))
+      .collect::<Vec<_>>()
+      .join(" ")
+      .replace("grey", "gray");
+    if !color.is_empty() && parse_color(&color).is_none() {
+      return Err(format!("Unknown color `{color}`"));
+    }
+  }
+  Ok(())
+}
+
+/// Splits a style string around its `on` word, in any case, into the foreground and
+/// background parts.
+fn split_background(line: &str) -> (&str, &str) {
+  let mut end = 0;
+  while let Some(start) = line[end..].find(|c: char| !c.is_whitespace()) {
+    let start = end + start;
+    end = line[start..]
+      .find(char::is_whitespace)
+      .map_or(line.len(), |len| start + len);
+    if line[start..end].eq_ignore_ascii_case("on") {
+      return (&line[..start], &line[end..]);
+    }
+  }
+  (line, "")
+}
+
 fn process_color_string(color_str: &str) -> (String, Modifier) {
   let color = color_str
     .replace("grey", "gray")
@@ -384,7 +618,10 @@ fn process_color_string(color_str: &str) -> (String, Modifier) {
 fn parse_color(s: &str) -> Option<Color> {
   let s = s.trim_start();
   let s = s.trim_end();
-  if s.contains("bright color") {
+  if let Some(hex) = s.strip_prefix('#').filter(|hex| hex.len() == 6) {
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+  } else if s.contains("bright color") {
     let s = s.trim_start_matches("bright ");
     let c = s
       .trim_start_matches("color")
@@ -494,6 +731,28 @@ mod tests {
     assert_eq!(color, Some(Color::Indexed(expected)));
   }
 
+  #[test]
+  fn test_parse_color_hex() {
+    assert_eq!(parse_color("#7ec10e"), Some(Color::Rgb(126, 193, 14)));
+    assert_eq!(parse_color("#7ec1"), None);
+    assert_eq!(parse_color("#zzzzzz"), None);
+  }
+
+  #[test]
+  fn test_validate_style() {
+    assert!(validate_style("bold inverse #00a3e1 on color11").is_ok());
+    assert!(validate_style("underline red on blue").is_ok());
+    assert!(validate_style("").is_ok());
+    assert!(validate_style("bold").is_ok());
+    assert!(validate_style("redish on blue").is_err());
+    assert!(validate_style("red on #12").is_err());
+    assert!(validate_style("red ON blue").is_ok());
+    // The Kelvin sign lowercases to a shorter `k`.
+    assert!(validate_style("K€ on red").is_err());
+    assert!(validate_style("İ on red").is_err());
+    assert_eq!(split_background("maroon on blue"), ("maroon ", " blue"));
+  }
+
   #[test]
   fn test_parse_color_unknown() {
     let color = parse_color("unknown");
@@ -514,6 +773,12 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_invalid_keybinding_is_an_error() {
+    let result = json5::from_str::<KeyBindings>(r#"{ "Home": { "<ctrl-nope>": "Quit" } }"#);
+    assert!(result.is_err());
+  }
+
   #[test]
   fn test_simple_keys() {
     assert_eq!(
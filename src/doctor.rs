@@ -0,0 +1,541 @@
+use std::{
+  cmp::Ordering,
+  collections::HashMap,
+  env, fmt, fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+use serde::Deserialize;
+use strum::{Display, VariantNames};
+
+use crate::{
+  action::Action,
+  app::Mode,
+  command,
+  config::{
+    get_config_dir, parse_key_sequence, validate_style, Config, HighlightConfig, CONFIG_FILES,
+  },
+  layout::LayoutPreset,
+  log_search,
+  project_manager::{ProjectManager, PACKAGE_MANAGER, WORKSPACE_ENV},
+  restart::RestartPolicy,
+  theme::Theme,
+  watch::IgnoreRules,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+  Warning,
+  Error,
+  /// Texus cannot start until this is fixed.
+  Fatal,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+  pub severity: Severity,
+  pub message: String,
+  pub fix: String,
+}
+
+impl Diagnostic {
+  fn new(severity: Severity, message: impl Into<String>, fix: impl Into<String>) -> Self {
+    Self {
+      severity,
+      message: message.into(),
+      fix: fix.into(),
+    }
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}: {}\n  fix: {}",
+      self.severity, self.message, self.fix
+    )
+  }
+}
+
+/// The subset of a config file that can be checked without deserializing it into `Config`.
+#[derive(Default, Deserialize)]
+struct RawConfig {
+  #[serde(default)]
+  keybindings: HashMap<String, HashMap<String, serde_json::Value>>,
+  #[serde(default)]
+  styles: HashMap<String, HashMap<String, String>>,
+  theme: Option<String>,
+  layout: Option<String>,
+  #[serde(default)]
+  highlights: Vec<HighlightConfig>,
+  interact_escape: Option<String>,
+  #[serde(default)]
+  projects: HashMap<String, RawProjectConfig>,
+  #[serde(default)]
+  watch_ignore: Vec<String>,
+}
+
+#[derive(Deserialize, Default)]
+struct RawProjectConfig {
+  restart_policy: Option<String>,
+}
+
+/// Runs every check and returns the problems found, most severe first.
+pub fn diagnose() -> Vec<Diagnostic> {
+  let mut diagnostics = diagnose_startup();
+  diagnostics.extend(diagnose_workspace());
+  diagnostics.sort_by_key(|diagnostic| std::cmp::Reverse(diagnostic.severity));
+  diagnostics
+}
+
+/// The quick checks that can keep texus from starting: the config files and the workspace
+/// root.
+pub fn diagnose_startup() -> Vec<Diagnostic> {
+  let mut diagnostics = check_config();
+  match ProjectManager::workspace_root() {
+    Some(root) if root.is_dir() => {}
+    Some(root) => diagnostics.push(Diagnostic::new(
+      Severity::Fatal,
+      format!("Workspace root `{}` is not a directory", root.display()),
+      format!("Point {WORKSPACE_ENV} at your monorepo in `.env` or the environment"),
+    )),
+    None => diagnostics.push(Diagnostic::new(
+      Severity::Fatal,
+      format!("{WORKSPACE_ENV} is not set"),
+      format!("Add `{WORKSPACE_ENV}=/path/to/your/monorepo` to `.env`, see `.env.default`"),
+    )),
+  }
+  diagnostics.sort_by_key(|diagnostic| std::cmp::Reverse(diagnostic.severity));
+  diagnostics
+}
+
+/// The slower checks of the package manager, Node and every package.json, which run `node`
+/// and read the whole workspace but never keep texus from starting.
+pub fn diagnose_workspace() -> Vec<Diagnostic> {
+  let Some(root) = ProjectManager::workspace_root().filter(|root| root.is_dir()) else {
+    return Vec::new();
+  };
+  let mut diagnostics: Vec<Diagnostic> = check_package_manager().into_iter().collect();
+  diagnostics.extend(check_node_version(&root));
+  diagnostics.extend(check_packages(&root));
+  diagnostics.sort_by_key(|diagnostic| std::cmp::Reverse(diagnostic.severity));
+  diagnostics
+}
+
+/// Prints the report for `texus doctor`.
+pub fn print(diagnostics: &[Diagnostic]) {
+  if diagnostics.is_empty() {
+    println!("No problems found.");
+    return;
+  }
+  for diagnostic in diagnostics {
+    println!("{diagnostic}\n");
+  }
+  let count = |severity| {
+    diagnostics
+      .iter()
+      .filter(|diagnostic| diagnostic.severity == severity)
+      .count()
+  };
+  println!(
+    "{} fatal, {} errors, {} warnings",
+    count(Severity::Fatal),
+    count(Severity::Error),
+    count(Severity::Warning)
+  );
+}
+
+pub fn check_config() -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  for (file, format) in CONFIG_FILES {
+    let path = get_config_dir().join(file);
+    if !path.exists() {
+      continue;
+    }
+    let raw = ::config::Config::builder()
+      .add_source(::config::File::from(path.as_path()).format(format))
+      .build()
+      .and_then(|source| source.try_deserialize::<RawConfig>());
+    match raw {
+      Ok(raw) => diagnostics.extend(check_raw_config(file, &raw)),
+      Err(err) => diagnostics.push(Diagnostic::new(
+        Severity::Fatal,
+        format!("{}: {err}", path.display()),
+        "Fix the syntax error; `keybindings` and `styles` must map modes to tables",
+      )),
+    }
+  }
+
+  // Anything the checks above missed still prevents the configuration from loading.
+  let has_fatal = diagnostics
+    .iter()
+    .any(|diagnostic| diagnostic.severity == Severity::Fatal);
+  if !has_fatal {
+    if let Err(err) = Config::new() {
+      diagnostics.push(Diagnostic::new(
+        Severity::Fatal,
+        format!("Configuration cannot be loaded: {err}"),
+        format!("Check the files in {}", get_config_dir().display()),
+      ));
+    }
+  }
+  diagnostics
+}
+
+fn check_raw_config(file: &str, raw: &RawConfig) -> Vec<Diagnostic> {
+  let mut diagnostics = Vec::new();
+  let modes = Mode::VARIANTS.join(", ");
+
+  for (mode, bindings) in &raw.keybindings {
+    // The `config` crate lowercases keys and matches variants case-insensitively.
+    if !Mode::VARIANTS
+      .iter()
+      .any(|variant| variant.eq_ignore_ascii_case(mode))
+    {
+      diagnostics.push(Diagnostic::new(
+        Severity::Fatal,
+        format!("{file}: unknown mode `{mode}` in `keybindings`"),
+        format!("Use one of: {modes}"),
+      ));
+    }
+    for (keys, action) in bindings {
+      if let Err(err) = parse_key_sequence(keys) {
+        diagnostics.push(Diagnostic::new(
+          Severity::Fatal,
+          format!("{file}: invalid key sequence `{keys}` in `keybindings.{mode}`: {err}"),
+          "Write keys like `<q>`, `<ctrl-d>` or `<g><g>`; modifiers are `ctrl-`, `alt-` and `shift-`",
+        ));
+      }
+      match parse_action(action) {
+        None => diagnostics.push(Diagnostic::new(
+          Severity::Fatal,
+          format!("{file}: invalid action {action} bound to `{keys}` in `keybindings.{mode}`"),
+          suggest_action(action),
+        )),
+        Some(Action::Command(line)) => {
+          if let Err(err) = command::parse(&line) {
+            diagnostics.push(Diagnostic::new(
+              Severity::Error,
+              format!(
+                "{file}: invalid command `{line}` bound to `{keys}` in `keybindings.{mode}`: {err}"
+              ),
+              format!(
+                "Use `|` to chain commands: {}",
+                command::COMMANDS.join(", ")
+              ),
+            ));
+          }
+        }
+        Some(_) => {}
+      }
+    }
+  }
+
+  let style_keys: Vec<&str> = Theme::style_keys().collect();
+  for (mode, styles) in &raw.styles {
+    for (key, style) in styles {
+      if let Err(err) = validate_style(style) {
+        diagnostics.push(Diagnostic::new(
+          Severity::Error,
+          format!("{file}: invalid style `{style}` for `styles.{mode}.{key}`: {err}"),
+          "Use `[bold|underline|inverse] <color> [on <color>]` with named, `colorN`, `grayN`, `rgbRGB` or `#rrggbb` colors",
+        ));
+      }
+      if !style_keys.contains(&key.as_str()) {
+        diagnostics.push(Diagnostic::new(
+          Severity::Warning,
+          format!("{file}: unknown style key `styles.{mode}.{key}` is ignored"),
+          format!("Use one of: {}", style_keys.join(", ")),
+        ));
+      }
+    }
+  }
+
+  let (_, errors) = log_search::compile_highlights(&raw.highlights);
+  diagnostics.extend(errors.into_iter().map(|error| {
+    Diagnostic::new(
+      Severity::Error,
+      format!("{file}: {error}, the rule is ignored"),
+      "Patterns use the `regex` crate syntax, e.g. `(?i)warn`",
+    )
+  }));
+
+  if let Some(escape) = raw.interact_escape.as_deref() {
+    if !matches!(parse_key_sequence(escape).as_deref(), Ok([_])) {
+      diagnostics.push(Diagnostic::new(
+        Severity::Error,
+        format!("{file}: invalid `interact_escape` key `{escape}`, using `<ctrl-g>`"),
+        "Use a single key like `<ctrl-g>` or `<f12>`",
+      ));
+    }
+  }
+
+  let (_, errors) = IgnoreRules::new(&raw.watch_ignore);
+  diagnostics.extend(errors.into_iter().map(|error| {
+    Diagnostic::new(
+      Severity::Error,
+      format!("{file}: {error}, the glob is ignored"),
+      "Write globs like `node_modules`, `*.log` or `src/generated/**`",
+    )
+  }));
+
+  for (project, config) in &raw.projects {
+    if let Some(policy) = config.restart_policy.as_deref() {
+      if !RestartPolicy::VARIANTS.contains(&policy) {
+        diagnostics.push(Diagnostic::new(
+          Severity::Fatal,
+          format!("{file}: unknown restart policy `{policy}` in `projects.{project}`"),
+          format!("Use one of: {}", RestartPolicy::VARIANTS.join(", ")),
+        ));
+      }
+    }
+  }
+
+  if let Some(layout) = raw.layout.as_deref() {
+    if !LayoutPreset::VARIANTS.contains(&layout) {
+      diagnostics.push(Diagnostic::new(
+        Severity::Fatal,
+        format!("{file}: unknown layout `{layout}`"),
+        format!("Use one of: {}", LayoutPreset::VARIANTS.join(", ")),
+      ));
+    }
+  }
+
+  if let Some(theme) = raw.theme.as_deref() {
+    if Theme::names().all(|name| name != theme) {
+      diagnostics.push(Diagnostic::new(
+        Severity::Error,
+        format!("{file}: unknown theme `{theme}`, using `dark`"),
+        format!(
+          "Use one of: {}",
+          Theme::names().collect::<Vec<_>>().join(", ")
+        ),
+      ));
+    }
+  }
+  diagnostics
+}
+
+fn parse_action(action: &serde_json::Value) -> Option<Action> {
+  let canonical = |name: &str| {
+    Action::VARIANTS
+      .iter()
+      .find(|variant| variant.eq_ignore_ascii_case(name))
+      .map(|variant| variant.to_string())
+  };
+  let action = match action {
+    serde_json::Value::String(name) => serde_json::Value::String(canonical(name)?),
+    serde_json::Value::Object(map) => map
+      .iter()
+      .map(|(name, payload)| Some((canonical(name)?, payload.clone())))
+      .collect::<Option<serde_json::Map<_, _>>>()?
+      .into(),
+    _ => return None,
+  };
+  serde_json::from_value(action).ok()
+}
+
+fn suggest_action(action: &serde_json::Value) -> String {
+  let name = match action {
+    serde_json::Value::String(name) => name.as_str(),
+    serde_json::Value::Object(map) => map.keys().next().map_or("", String::as_str),
+    _ => "",
+  };
+  let name_lower = name.to_lowercase();
+  let close = Action::VARIANTS.iter().find(|variant| {
+    let variant = variant.to_lowercase();
+    variant.starts_with(&name_lower) || name_lower.starts_with(&variant)
+  });
+  match close {
+    Some(variant) if !name.is_empty() => format!("Did you mean `{variant}`?"),
+    _ => format!("Use one of: {}", Action::VARIANTS.join(", ")),
+  }
+}
+
+/// Finds `name` in the directories of `PATH`.
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+  env::split_paths(&env::var_os("PATH")?)
+    .map(|dir| dir.join(name))
+    .find(|path| path.is_file())
+}
+
+fn check_package_manager() -> Option<Diagnostic> {
+  find_executable(PACKAGE_MANAGER).is_none().then(|| {
+    Diagnostic::new(
+      Severity::Error,
+      format!("`{PACKAGE_MANAGER}` was not found on PATH, scripts cannot be started"),
+      format!("Install it with `corepack enable` or `npm install -g {PACKAGE_MANAGER}`"),
+    )
+  })
+}
+
+fn check_node_version(root: &Path) -> Vec<Diagnostic> {
+  let Some(node) = find_executable("node") else {
+    return vec![Diagnostic::new(
+      Severity::Error,
+      "`node` was not found on PATH",
+      "Install Node.js, for example with nvm or fnm",
+    )];
+  };
+  let Some(version) = Command::new(node)
+    .arg("--version")
+    .output()
+    .ok()
+    .and_then(|output| parse_version(&String::from_utf8_lossy(&output.stdout)))
+  else {
+    return Vec::new();
+  };
+  let installed = version
+    .iter()
+    .map(u64::to_string)
+    .collect::<Vec<_>>()
+    .join(".");
+
+  let mut requirements: Vec<(String, String)> = [".nvmrc", ".node-version"]
+    .into_iter()
+    .filter_map(|file| {
+      let required = fs::read_to_string(root.join(file)).ok()?;
+      Some((file.to_string(), required.trim().to_string()))
+    })
+    .collect();
+  if let Some(required) = read_package_json(&root.join("package.json"))
+    .and_then(|package| package["engines"]["node"].as_str().map(String::from))
+  {
+    requirements.push(("package.json `engines.node`".to_string(), required));
+  }
+
+  requirements
+    .into_iter()
+    .filter(|(_, required)| satisfies(&version, required) == Some(false))
+    .map(|(source, required)| {
+      Diagnostic::new(
+        Severity::Warning,
+        format!("Node {installed} does not satisfy `{required}` from {source}"),
+        "Switch versions with `nvm use` or `fnm use`, or update the requirement",
+      )
+    })
+    .collect()
+}
+
+fn read_package_json(path: &Path) -> Option<serde_json::Value> {
+  serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+fn check_packages(root: &Path) -> Vec<Diagnostic> {
+  fs::read_dir(root)
+    .into_iter()
+    .flat_map(|entries| entries.filter_map(Result::ok))
+    .map(|entry| entry.path().join("package.json"))
+    .filter(|path| path.exists())
+    .filter_map(|path| match fs::read_to_string(&path) {
+      Err(err) => Some(Diagnostic::new(
+        Severity::Error,
+        format!("Cannot read {}: {err}", path.display()),
+        "Check the file permissions; the project is hidden until it can be read",
+      )),
+      Ok(content) => serde_json::from_str::<serde_json::Value>(&content)
+        .err()
+        .map(|err| {
+          Diagnostic::new(
+            Severity::Error,
+            format!("{} is not valid JSON: {err}", path.display()),
+            "Fix the JSON syntax; scripts and dependencies of this project are ignored",
+          )
+        }),
+    })
+    .collect()
+}
+
+/// Parses `v18.17.0`, `18.17` or `18` into its numeric components.
+fn parse_version(raw: &str) -> Option<Vec<u64>> {
+  let raw = raw.trim().trim_start_matches(['v', '=']);
+  let version: Vec<u64> = raw.split('.').map_while(|part| part.parse().ok()).collect();
+  (!version.is_empty()).then_some(version)
+}
+
+/// Checks `version` against an npm-style range, or `None` when the range is not understood.
+fn satisfies(version: &[u64], range: &str) -> Option<bool> {
+  let mut any = false;
+  for alternative in range.split("||") {
+    let mut all = true;
+    for comparator in alternative.split_whitespace() {
+      let (op, rest) = [">=", "<=", ">", "<", "^", "~", "="]
+        .into_iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (op, rest)))
+        .unwrap_or(("", comparator));
+      let required = parse_version(rest)?;
+      let len = required.len().min(version.len());
+      let ordering = version[..len].cmp(&required[..len]);
+      all &= match op {
+        ">=" => ordering != Ordering::Less,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        "^" => version.first() == required.first() && ordering != Ordering::Less,
+        "~" => version.iter().take(2).eq(required.iter().take(2)) && ordering != Ordering::Less,
+        _ => ordering == Ordering::Equal,
+      };
+    }
+    any |= all;
+  }
+  Some(any)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_version() {
+    assert_eq!(parse_version("v20.11.1\n"), Some(vec![20, 11, 1]));
+    assert_eq!(parse_version("18"), Some(vec![18]));
+    assert_eq!(parse_version("18.x"), Some(vec![18]));
+    assert_eq!(parse_version("lts/*"), None);
+  }
+
+  #[test]
+  fn test_satisfies() {
+    let version = [20, 11, 1];
+    assert_eq!(satisfies(&version, "20"), Some(true));
+    assert_eq!(satisfies(&version, "v18.17.0"), Some(false));
+    assert_eq!(satisfies(&version, ">=18"), Some(true));
+    assert_eq!(satisfies(&version, ">=16 <20"), Some(false));
+    assert_eq!(satisfies(&version, "^18.0.0 || ^20.0.0"), Some(true));
+    assert_eq!(satisfies(&version, "~20.10"), Some(false));
+    assert_eq!(satisfies(&version, "lts/iron"), None);
+  }
+
+  #[test]
+  fn test_raw_config_diagnostics() {
+    let raw: RawConfig = json5::from_str(
+      r#"{
+        "keybindings": { "Home": { "<ctrl-nope>": "Quit", "<x>": "Quitt" }, "Homer": {} },
+        "styles": { "Home": { "scrollbar": "redish", "border.activ": "red" } },
+        "theme": "neon",
+        "projects": { "api": { "restart_policy": "on-crash" } },
+      }"#,
+    )
+    .unwrap();
+    let diagnostics = check_raw_config("config.json5", &raw);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(diagnostics.len(), 7, "{messages:#?}");
+    assert!(messages.iter().any(|m| m.contains("unknown mode `Homer`")));
+    assert!(messages.iter().any(|m| m.contains("`<ctrl-nope>`")));
+    assert!(diagnostics
+      .iter()
+      .any(|d| d.message.contains("\"Quitt\"") && d.fix == "Did you mean `Quit`?"));
+    assert!(messages
+      .iter()
+      .any(|m| m.contains("invalid style `redish`")));
+    assert!(messages
+      .iter()
+      .any(|m| m.contains("`styles.Home.border.activ`")));
+    assert!(messages.iter().any(|m| m.contains("unknown theme `neon`")));
+    assert!(messages
+      .iter()
+      .any(|m| m.contains("unknown restart policy `on-crash`")));
+  }
+}
@@ -0,0 +1,201 @@
+use std::{
+  collections::HashMap,
+  env,
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config::EditorConfig;
+
+lazy_static! {
+  /// `path/to/file.ext:line:column`, `file.ext(line,column)` or `file.ext line:column`.
+  static ref LOCATION: Regex = Regex::new(
+    r"(?P<file>(?:[A-Za-z]:)?[\w.@~/\\-]*\w\.[A-Za-z]\w*)(?::(?P<line>\d+)(?::(?P<column>\d+))?|\((?P<line2>\d+),(?P<column2>\d+)\)| (?P<line3>\d+):(?P<column3>\d+))"
+  )
+  .unwrap();
+}
+
+/// Commands of well-known editors, used unless `editors` configures them differently.
+const BUILT_IN: &[(&str, &str, bool)] = &[
+  ("vim", "vim +{line} {file}", true),
+  ("nvim", "nvim +{line} {file}", true),
+  ("code", "code --goto {file}:{line}:{column}", false),
+  ("hx", "hx {file}:{line}:{column}", true),
+  ("emacs", "emacs -nw +{line}:{column} {file}", true),
+];
+
+/// A position in a file, as printed by compilers and linters.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileLocation {
+  pub path: PathBuf,
+  pub line: u32,
+  pub column: u32,
+}
+
+impl FileLocation {
+  /// Resolves `file` against the directory of the project that printed it.
+  pub fn new(project_dir: &Path, file: &str, line: u32, column: u32) -> Self {
+    Self {
+      path: project_dir.join(file),
+      line,
+      column,
+    }
+  }
+}
+
+/// The last `file:line[:column]` in `text`, with the column defaulting to 1.
+pub fn find_location(text: &str) -> Option<(String, u32, u32)> {
+  let captures = LOCATION.captures_iter(text).last()?;
+  let number = |names: [&str; 3]| {
+    names
+      .iter()
+      .find_map(|name| captures.name(name))
+      .and_then(|value| value.as_str().parse().ok())
+  };
+  Some((
+    captures["file"].to_string(),
+    number(["line", "line2", "line3"])?,
+    number(["column", "column2", "column3"]).unwrap_or(1),
+  ))
+}
+
+/// The configured editor, else `$VISUAL`, else `$EDITOR`.
+pub fn editor(configured: Option<&str>) -> Option<String> {
+  configured
+    .map(str::to_string)
+    .or_else(|| env::var("VISUAL").ok())
+    .or_else(|| env::var("EDITOR").ok())
+    .filter(|editor| !editor.trim().is_empty())
+}
+
+/// An editor command ready to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditorCommand {
+  pub program: String,
+  pub args: Vec<String>,
+  /// Whether the editor runs in the terminal, which the TUI has to hand over.
+  pub terminal: bool,
+}
+
+impl EditorCommand {
+  /// Fills the command template of `editor` in with `location`.
+  ///
+  /// `editor` may carry arguments, e.g. `code -w`; its program name selects the template,
+  /// falling back to opening the file in a terminal editor.
+  pub fn new(
+    editor: &str,
+    editors: &HashMap<String, EditorConfig>,
+    location: &FileLocation,
+  ) -> Self {
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let extra_args: Vec<&str> = words.collect();
+    let name = Path::new(program)
+      .file_stem()
+      .and_then(|name| name.to_str())
+      .unwrap_or(program);
+
+    let (template, terminal) = match editors.get(name) {
+      Some(config) => (config.command.clone(), config.terminal),
+      None => BUILT_IN
+        .iter()
+        .find(|(built_in, _, _)| *built_in == name)
+        .map(|(_, command, terminal)| (command.to_string(), *terminal))
+        .unwrap_or_else(|| (format!("{name} {{file}}"), true)),
+    };
+
+    let file = location.path.to_string_lossy();
+    let mut words = template.split_whitespace().map(|word| {
+      word
+        .replace("{file}", &file)
+        .replace("{line}", &location.line.to_string())
+        .replace("{column}", &location.column.to_string())
+    });
+    // The template's program is replaced by the one configured, which may be a full path.
+    words.next();
+    let args = extra_args
+      .into_iter()
+      .map(str::to_string)
+      .chain(words)
+      .collect();
+    Self {
+      program: program.to_string(),
+      args,
+      terminal,
+    }
+  }
+
+  /// Runs a terminal editor until it exits, or starts a graphical one in the background.
+  pub fn run(&self) -> Result<(), String> {
+    let mut command = Command::new(&self.program);
+    command.args(&self.args);
+    let result = if self.terminal {
+      command.status().map(|_| ())
+    } else {
+      command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+    };
+    result.map_err(|err| format!("Failed to start `{}`: {err}", self.program))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_find_location() {
+    assert_eq!(
+      find_location("ERROR in ./src/Button.tsx:42:7"),
+      Some(("./src/Button.tsx".to_string(), 42, 7))
+    );
+    assert_eq!(
+      find_location("src/App.tsx(3,1): error TS2304"),
+      Some(("src/App.tsx".to_string(), 3, 1))
+    );
+    assert_eq!(
+      find_location("    at render (/repo/src/index.js:10)"),
+      Some(("/repo/src/index.js".to_string(), 10, 1))
+    );
+    assert_eq!(find_location("compiled in 1.2s"), None);
+  }
+
+  #[test]
+  fn test_editor_command() {
+    let location = FileLocation::new(Path::new("/repo/apps/web"), "src/Button.tsx", 42, 7);
+    let mut editors = HashMap::new();
+
+    let nvim = EditorCommand::new("/usr/bin/nvim", &editors, &location);
+    assert_eq!(nvim.program, "/usr/bin/nvim");
+    assert_eq!(nvim.args, vec!["+42", "/repo/apps/web/src/Button.tsx"]);
+    assert!(nvim.terminal);
+
+    let code = EditorCommand::new("code -w", &editors, &location);
+    assert_eq!(
+      code.args,
+      vec!["-w", "--goto", "/repo/apps/web/src/Button.tsx:42:7"]
+    );
+    assert!(!code.terminal);
+
+    editors.insert(
+      "subl".to_string(),
+      EditorConfig {
+        command: "subl {file}:{line}:{column}".to_string(),
+        terminal: false,
+      },
+    );
+    let subl = EditorCommand::new("subl", &editors, &location);
+    assert_eq!(subl.args, vec!["/repo/apps/web/src/Button.tsx:42:7"]);
+
+    let nano = EditorCommand::new("nano", &editors, &location);
+    assert_eq!(nano.args, vec!["/repo/apps/web/src/Button.tsx"]);
+  }
+}
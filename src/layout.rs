@@ -0,0 +1,95 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, VariantNames};
+
+/// Arrangements of the list, details and status panels.
+#[derive(
+  Debug,
+  Default,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Display,
+  EnumString,
+  VariantNames,
+  Serialize,
+  Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum LayoutPreset {
+  /// Three columns side by side, resizable by dragging their borders.
+  #[default]
+  Columns,
+  /// The panels on top of each other.
+  Stacked,
+  /// The list above the details on the left, the output filling the rest.
+  LogsFocused,
+}
+
+/// Areas of the list, details and status panels; hidden panels get an empty area.
+///
+/// Only the `focused` panel is shown when `zoomed` or when `area` is narrower than
+/// `collapse_width`.
+pub fn panel_areas(
+  preset: LayoutPreset,
+  column_widths: [u16; 3],
+  area: Rect,
+  focused: usize,
+  zoomed: bool,
+  collapse_width: u16,
+) -> [Rect; 3] {
+  if zoomed || area.width < collapse_width {
+    let mut areas = [Rect::default(); 3];
+    areas[focused] = area;
+    return areas;
+  }
+  match preset {
+    LayoutPreset::Columns => {
+      Layout::horizontal(column_widths.map(Constraint::Percentage)).areas(area)
+    }
+    LayoutPreset::Stacked => Layout::vertical([
+      Constraint::Percentage(30),
+      Constraint::Percentage(25),
+      Constraint::Percentage(45),
+    ])
+    .areas(area),
+    LayoutPreset::LogsFocused => {
+      let [left, status] =
+        Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)]).areas(area);
+      let [list, detail] =
+        Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(left);
+      [list, detail, status]
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_panel_areas() {
+    let area = Rect::new(0, 0, 100, 40);
+    let widths = [25, 30, 45];
+    let columns = panel_areas(LayoutPreset::Columns, widths, area, 0, false, 60);
+    assert_eq!(columns.map(|rect| rect.width), [25, 30, 45]);
+
+    let stacked = panel_areas(LayoutPreset::Stacked, widths, area, 0, false, 60);
+    assert!(stacked.iter().all(|rect| rect.width == 100));
+
+    let zoomed = panel_areas(LayoutPreset::LogsFocused, widths, area, 2, true, 60);
+    assert_eq!(zoomed, [Rect::default(), Rect::default(), area]);
+
+    let narrow = Rect::new(0, 0, 50, 40);
+    let collapsed = panel_areas(LayoutPreset::Columns, widths, narrow, 1, false, 60);
+    assert_eq!(collapsed, [Rect::default(), narrow, Rect::default()]);
+  }
+
+  #[test]
+  fn test_preset_names() {
+    assert_eq!(LayoutPreset::LogsFocused.to_string(), "logs-focused");
+    assert_eq!("stacked".parse(), Ok(LayoutPreset::Stacked));
+  }
+}
@@ -0,0 +1,248 @@
+use std::ops::Range;
+
+use ratatui::{
+  style::Style,
+  text::{Line, Span},
+};
+use regex::Regex;
+
+use crate::config::{parse_style, validate_style, HighlightConfig};
+
+/// Output text matching `regex` is drawn with `style`.
+#[derive(Debug, Clone)]
+pub struct HighlightRule {
+  pub regex: Regex,
+  pub style: Style,
+}
+
+/// Compiles the configured highlight rules, skipping and reporting the invalid ones.
+pub fn compile_highlights(rules: &[HighlightConfig]) -> (Vec<HighlightRule>, Vec<String>) {
+  let mut compiled = Vec::new();
+  let mut errors = Vec::new();
+  for rule in rules {
+    match (Regex::new(&rule.pattern), validate_style(&rule.style)) {
+      (Ok(regex), Ok(())) => compiled.push(HighlightRule {
+        regex,
+        style: parse_style(&rule.style),
+      }),
+      (Err(err), _) => errors.push(format!(
+        "Invalid highlight pattern `{}`: {err}",
+        rule.pattern
+      )),
+      (_, Err(err)) => errors.push(format!("Invalid highlight style `{}`: {err}", rule.style)),
+    }
+  }
+  (compiled, errors)
+}
+
+/// The prompt being typed below the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogPrompt {
+  /// `/`: jump between lines matching a regex.
+  Search,
+  /// `&`: only show lines matching a regex, or not matching it with a leading `!`.
+  Filter,
+}
+
+/// Search and filter applied to project output.
+#[derive(Debug, Default)]
+pub struct LogSearchState {
+  pub prompt: Option<LogPrompt>,
+  pub input: String,
+  pub search: Option<Regex>,
+  pub filter: Option<Regex>,
+  /// Hide the lines matching `filter` instead of showing only them.
+  pub invert_filter: bool,
+  /// The match the view was last moved to, as an index into the matching lines.
+  pub current: Option<usize>,
+}
+
+impl LogSearchState {
+  pub fn open(&mut self, prompt: LogPrompt) {
+    self.prompt = Some(prompt);
+    self.input.clear();
+  }
+
+  /// Applies the typed prompt; an empty input clears the search or filter.
+  pub fn submit(&mut self) -> Result<(), String> {
+    let Some(prompt) = self.prompt.take() else {
+      return Ok(());
+    };
+    let (pattern, invert) = match (prompt, self.input.strip_prefix('!')) {
+      (LogPrompt::Filter, Some(pattern)) => (pattern, true),
+      _ => (self.input.as_str(), false),
+    };
+    let regex = if pattern.is_empty() {
+      None
+    } else {
+      Some(Regex::new(pattern).map_err(|err| format!("Invalid regex `{pattern}`: {err}"))?)
+    };
+    match prompt {
+      LogPrompt::Search => self.search = regex,
+      LogPrompt::Filter => {
+        self.filter = regex;
+        self.invert_filter = invert;
+      }
+    }
+    self.current = None;
+    Ok(())
+  }
+
+  /// The lines of `output` that pass the filter.
+  pub fn visible_lines<'a>(&self, output: &'a str) -> Vec<&'a str> {
+    output
+      .lines()
+      .filter(|line| match &self.filter {
+        Some(filter) => filter.is_match(line) != self.invert_filter,
+        None => true,
+      })
+      .collect()
+  }
+
+  /// Indices into `lines` of the lines matching the search.
+  pub fn matching_lines(&self, lines: &[&str]) -> Vec<usize> {
+    let Some(search) = &self.search else {
+      return Vec::new();
+    };
+    lines
+      .iter()
+      .enumerate()
+      .filter(|(_, line)| search.is_match(line))
+      .map(|(index, _)| index)
+      .collect()
+  }
+
+  /// Moves to the next or previous match from the line at `scroll`, wrapping around.
+  /// Returns the line to scroll to.
+  pub fn jump(&mut self, lines: &[&str], scroll: usize, forward: bool) -> Option<usize> {
+    let matches = self.matching_lines(lines);
+    if matches.is_empty() {
+      return None;
+    }
+    let index = match (self.current, forward) {
+      (Some(current), true) => (current + 1) % matches.len(),
+      (Some(current), false) => (current + matches.len() - 1) % matches.len(),
+      (None, true) => matches.iter().position(|&line| line >= scroll).unwrap_or(0),
+      (None, false) => matches
+        .iter()
+        .rposition(|&line| line < scroll)
+        .unwrap_or(matches.len() - 1),
+    };
+    self.current = Some(index);
+    Some(matches[index])
+  }
+
+  /// Styles `line` with the highlight rules, then the search matches with `match_style`,
+  /// and splits it into the byte `ranges` of the rows it wraps to.
+  pub fn highlight<'a>(
+    &self,
+    line: &'a str,
+    ranges: &[Range<usize>],
+    rules: &[HighlightRule],
+    match_style: Style,
+  ) -> Vec<Line<'a>> {
+    let mut styles = vec![Style::default(); line.len()];
+    let rules = rules
+      .iter()
+      .map(|rule| (&rule.regex, rule.style))
+      .chain(self.search.iter().map(|search| (search, match_style)));
+    for (regex, style) in rules {
+      for found in regex.find_iter(line) {
+        for byte_style in &mut styles[found.range()] {
+          *byte_style = byte_style.patch(style);
+        }
+      }
+    }
+
+    ranges
+      .iter()
+      .map(|range| {
+        let mut spans = Vec::new();
+        let mut start = range.start;
+        for (byte, _) in line[range.clone()].char_indices().skip(1) {
+          let byte = range.start + byte;
+          if styles[byte] != styles[start] {
+            spans.push(Span::styled(&line[start..byte], styles[start]));
+            start = byte;
+          }
+        }
+        if start < range.end {
+          spans.push(Span::styled(&line[start..range.end], styles[start]));
+        }
+        Line::from(spans)
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use ratatui::style::Stylize;
+
+  use super::*;
+
+  const OUTPUT: &str = "compiled\nWARN slow build\nERROR failed\nwarn again";
+
+  #[test]
+  fn test_filter_and_jump() {
+    let mut search = LogSearchState::default();
+    search.open(LogPrompt::Filter);
+    search.input = "!^compiled".to_string();
+    search.submit().unwrap();
+    let lines = search.visible_lines(OUTPUT);
+    assert_eq!(lines, vec!["WARN slow build", "ERROR failed", "warn again"]);
+
+    search.open(LogPrompt::Search);
+    search.input = "(?i)warn".to_string();
+    search.submit().unwrap();
+    assert_eq!(search.matching_lines(&lines), vec![0, 2]);
+    assert_eq!(search.jump(&lines, 1, true), Some(2));
+    assert_eq!(search.jump(&lines, 2, true), Some(0));
+    assert_eq!(search.jump(&lines, 0, false), Some(2));
+
+    search.open(LogPrompt::Search);
+    search.input = "(".to_string();
+    assert!(search.submit().is_err());
+  }
+
+  #[test]
+  fn test_highlight() {
+    let (rules, errors) = compile_highlights(&[
+      HighlightConfig {
+        pattern: "ERROR".to_string(),
+        style: "red".to_string(),
+      },
+      HighlightConfig {
+        pattern: "[".to_string(),
+        style: "red".to_string(),
+      },
+    ]);
+    assert_eq!(errors.len(), 1);
+
+    let search = LogSearchState {
+      search: Regex::new("fail").ok(),
+      ..Default::default()
+    };
+    let lines = search.highlight(
+      "ERROR failed",
+      &[0..8, 8..12],
+      &rules,
+      Style::new().underlined(),
+    );
+    assert_eq!(
+      lines[0].spans,
+      vec![
+        Span::styled("ERROR", parse_style("red")),
+        Span::raw(" "),
+        Span::styled("fa", Style::new().underlined()),
+      ]
+    );
+    assert_eq!(
+      lines[1].spans,
+      vec![
+        Span::styled("il", Style::new().underlined()),
+        Span::raw("ed"),
+      ]
+    );
+  }
+}
@@ -0,0 +1,191 @@
+use unicode_width::UnicodeWidthChar;
+
+/// Part of a log line that fits on one row of a view: bytes `start..end` of line `line`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+  pub line: usize,
+  pub start: usize,
+  pub end: usize,
+}
+
+/// The first of the `rows` showing `line`.
+pub fn first_row(rows: &[Row], line: usize) -> usize {
+  rows.partition_point(|row| row.line < line)
+}
+
+/// Scroll position of a log that sticks to its newest line until scrolled up. Positions
+/// count rows, which are lines unless the view wraps them, see [`LogView::rows`].
+#[derive(Debug, Clone)]
+pub struct LogView {
+  /// First row shown while not following.
+  pub scroll: usize,
+  pub follow: bool,
+  /// Rows that fit in the view when it was last drawn.
+  pub height: usize,
+  /// Columns of the view when it was last drawn, where lines wrap; 0 to not wrap.
+  pub width: usize,
+  /// Length of the log when following stopped, to count the rows that arrived since.
+  paused_at: usize,
+}
+
+impl Default for LogView {
+  fn default() -> Self {
+    Self {
+      scroll: 0,
+      follow: true,
+      height: 0,
+      width: 0,
+      paused_at: 0,
+    }
+  }
+}
+
+impl LogView {
+  fn bottom(&self, len: usize) -> usize {
+    len.saturating_sub(self.height)
+  }
+
+  /// Splits `lines` into the rows they wrap to at the width of the view.
+  pub fn rows(&self, lines: &[&str]) -> Vec<Row> {
+    let mut rows = Vec::with_capacity(lines.len());
+    for (index, line) in lines.iter().enumerate() {
+      let mut start = 0;
+      let mut width = 0;
+      for (byte, char) in line.char_indices() {
+        let char_width = char.width().unwrap_or_default();
+        if self.width > 0 && byte > start && width + char_width > self.width {
+          rows.push(Row {
+            line: index,
+            start,
+            end: byte,
+          });
+          start = byte;
+          width = 0;
+        }
+        width += char_width;
+      }
+      rows.push(Row {
+        line: index,
+        start,
+        end: line.len(),
+      });
+    }
+    rows
+  }
+
+  /// First row shown of a log with `len` rows.
+  pub fn first_line(&self, len: usize) -> usize {
+    if self.follow {
+      self.bottom(len)
+    } else {
+      self.scroll.min(self.bottom(len))
+    }
+  }
+
+  /// Rows that arrived since following stopped.
+  pub fn new_lines(&self, len: usize) -> usize {
+    if self.follow {
+      0
+    } else {
+      len.saturating_sub(self.paused_at)
+    }
+  }
+
+  /// Lines that started since following stopped, of a log wrapped to `rows`.
+  pub fn new_wrapped_lines(&self, rows: &[Row]) -> usize {
+    let new_rows = self.new_lines(rows.len());
+    rows[rows.len() - new_rows..]
+      .iter()
+      .filter(|row| row.start == 0)
+      .count()
+  }
+
+  /// Shows `row` at the top and stops following.
+  pub fn scroll_to(&mut self, len: usize, row: usize) {
+    if self.follow {
+      self.paused_at = len;
+    }
+    self.scroll = row.min(self.bottom(len));
+    self.follow = false;
+  }
+
+  /// Scrolls by `delta` rows; scrolling down to the end follows new lines again.
+  pub fn scroll_by(&mut self, len: usize, delta: i32) {
+    let row = (self.first_line(len) as i32 + delta).max(0) as usize;
+    if delta > 0 && row >= self.bottom(len) {
+      self.follow = true;
+    } else {
+      self.scroll_to(len, row);
+    }
+  }
+
+  pub fn set_follow(&mut self, len: usize, follow: bool) {
+    if follow {
+      self.follow = true;
+    } else {
+      let line = self.first_line(len);
+      self.scroll_to(len, line);
+    }
+  }
+
+  /// Half the view, the distance of a half-page jump.
+  pub fn half_page(&self) -> i32 {
+    (self.height / 2).max(1) as i32
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_follow_pauses_and_resumes() {
+    let mut view = LogView {
+      height: 10,
+      ..Default::default()
+    };
+    assert_eq!(view.first_line(25), 15);
+
+    view.scroll_by(25, -5);
+    assert!(!view.follow);
+    assert_eq!(view.first_line(40), 10);
+    assert_eq!(view.new_lines(40), 15);
+
+    view.scroll_by(40, 10);
+    assert_eq!(view.first_line(40), 20);
+    view.scroll_by(40, view.half_page() * 2);
+    assert!(view.follow);
+    assert_eq!(view.new_lines(40), 0);
+
+    view.scroll_to(40, 0);
+    assert_eq!(view.first_line(50), 0);
+    view.set_follow(50, true);
+    assert_eq!(view.first_line(50), 40);
+  }
+
+  #[test]
+  fn test_rows_wrap_lines() {
+    let mut view = LogView {
+      height: 2,
+      width: 4,
+      ..Default::default()
+    };
+    let row = |line, start, end| Row { line, start, end };
+    let rows = view.rows(&["abcdefghij", "", "日本語"]);
+    assert_eq!(
+      rows,
+      vec![
+        row(0, 0, 4),
+        row(0, 4, 8),
+        row(0, 8, 10),
+        row(1, 0, 0),
+        row(2, 0, 6),
+        row(2, 6, 9),
+      ]
+    );
+    assert_eq!(first_row(&rows, 2), 4);
+
+    view.scroll_to(4, 0);
+    assert_eq!(view.new_wrapped_lines(&rows), 1);
+  }
+}
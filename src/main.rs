@@ -1,19 +1,38 @@
 use clap::Parser;
-use cli::Cli;
+use cli::{Cli, Command};
 use color_eyre::Result;
+use doctor::Severity;
 
 use crate::app::App;
 
 mod action;
 mod app;
 mod cli;
+mod command;
 mod components;
 mod config;
+mod doctor;
+mod editor;
 mod errors;
+mod layout;
+mod log_search;
+mod log_view;
 mod logging;
+mod palette;
+mod ports;
+mod problems;
 mod project_manager;
+mod pty;
+mod restart;
+mod sampler;
+mod search;
+mod shell;
+mod theme;
+mod timeline;
 mod tui;
 mod ui;
+mod watch;
+mod workspace;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -21,7 +40,30 @@ async fn main() -> Result<()> {
   crate::logging::init()?;
 
   let args = Cli::parse();
+  if let Some(Command::Doctor) = args.command {
+    let diagnostics = doctor::diagnose();
+    doctor::print(&diagnostics);
+    let failed = diagnostics
+      .iter()
+      .any(|diagnostic| diagnostic.severity >= Severity::Error);
+    std::process::exit(if failed {
+      libc::EXIT_FAILURE
+    } else {
+      libc::EXIT_SUCCESS
+    });
+  }
+  let diagnostics = doctor::diagnose_startup();
+  if diagnostics
+    .iter()
+    .any(|diagnostic| diagnostic.severity == Severity::Fatal)
+  {
+    doctor::print(&diagnostics);
+    std::process::exit(libc::EXIT_FAILURE);
+  }
+
   let mut app = App::new(args.tick_rate, args.frame_rate)?;
+  app.report(&diagnostics)?;
+  app.check_workspace();
   app.run().await?;
   Ok(())
 }
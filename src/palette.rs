@@ -0,0 +1,126 @@
+use crate::{
+  action::Action,
+  search::{self, History, Match},
+};
+
+#[derive(Debug, Clone)]
+pub struct PaletteEntry {
+  pub label: String,
+  /// Keybinding that triggers the same action, if any.
+  pub keys: Option<String>,
+  pub action: Action,
+}
+
+/// State of the command palette; entries are rebuilt every time it opens.
+#[derive(Debug, Default)]
+pub struct PaletteState {
+  pub visible: bool,
+  pub query: String,
+  pub selected: usize,
+  pub entries: Vec<PaletteEntry>,
+  recent: History,
+}
+
+impl PaletteState {
+  pub fn open(&mut self, entries: Vec<PaletteEntry>) {
+    self.visible = true;
+    self.query.clear();
+    self.selected = 0;
+    self.entries = entries;
+  }
+
+  pub fn close(&mut self) {
+    self.visible = false;
+    self.entries.clear();
+  }
+
+  /// Entries matching the query; without one, recently used entries come first.
+  pub fn matches(&self) -> Vec<Match> {
+    if !self.query.is_empty() {
+      return search::rank(
+        self.entries.iter().map(|entry| entry.label.as_str()),
+        &self.query,
+      );
+    }
+    let position = |label: &str| {
+      self
+        .recent
+        .entries()
+        .iter()
+        .rev()
+        .position(|recent| recent == label)
+        .unwrap_or(usize::MAX)
+    };
+    let mut matches: Vec<Match> = (0..self.entries.len())
+      .map(|index| Match {
+        index,
+        score: 0,
+        indices: Vec::new(),
+      })
+      .collect();
+    matches.sort_by_key(|m| position(&self.entries[m.index].label));
+    matches
+  }
+
+  pub fn select_by(&mut self, delta: i32) {
+    let max = self.matches().len().saturating_sub(1);
+    self.selected = ((self.selected as i32 + delta).max(0) as usize).min(max);
+  }
+
+  pub fn set_query(&mut self, query: String) {
+    self.query = query;
+    self.selected = 0;
+  }
+
+  /// Closes the palette and returns the action of the selected entry.
+  pub fn confirm(&mut self) -> Option<Action> {
+    let entry = self
+      .matches()
+      .get(self.selected)
+      .map(|m| self.entries[m.index].clone());
+    self.close();
+    let entry = entry?;
+    self.recent.push(&entry.label);
+    Some(entry.action)
+  }
+
+  pub fn is_recent(&self, entry: &PaletteEntry) -> bool {
+    self.recent.entries().contains(&entry.label)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn entries() -> Vec<PaletteEntry> {
+    ["Quit", "Show help", "Run test in web-app"]
+      .into_iter()
+      .map(|label| PaletteEntry {
+        label: label.to_string(),
+        keys: None,
+        action: Action::Notify(label.to_string()),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_recent_entries_come_first() {
+    let mut palette = PaletteState::default();
+    palette.open(entries());
+    palette.set_query("web".to_string());
+    assert_eq!(
+      palette.confirm(),
+      Some(Action::Notify("Run test in web-app".to_string()))
+    );
+    assert!(!palette.visible);
+
+    palette.open(entries());
+    palette.select_by(1);
+    assert_eq!(palette.confirm(), Some(Action::Notify("Quit".to_string())));
+
+    palette.open(entries());
+    let order: Vec<usize> = palette.matches().iter().map(|m| m.index).collect();
+    assert_eq!(order, vec![0, 2, 1]);
+  }
+}
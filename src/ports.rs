@@ -0,0 +1,343 @@
+use std::{
+  collections::HashMap,
+  fs,
+  io::{self, Write},
+  net::{Ipv4Addr, Ipv6Addr},
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+  thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{project_manager::Project, sampler::Snapshot};
+
+/// The `st` column of `/proc/net/tcp` for listening sockets.
+const LISTEN: &str = "0A";
+
+/// Most parents followed from a socket's owner to find the project that started it.
+const MAX_DEPTH: usize = 32;
+
+/// A TCP socket listening for connections.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListeningPort {
+  pub port: u16,
+  /// E.g. `127.0.0.1` or `::`.
+  pub address: String,
+  /// The process holding the socket, unknown for processes of other users.
+  pub pid: Option<u32>,
+  pub command: Option<String>,
+  /// The project whose script started the process, or whose directory it runs in.
+  pub project: Option<String>,
+}
+
+/// The address of a dev server listening on `port`.
+pub fn url(port: u16) -> String {
+  format!("http://localhost:{port}")
+}
+
+/// The listening TCP sockets of the machine, by port, with their owners read from `/proc`.
+/// Reads the descriptors of every process, so call it off the UI thread.
+pub fn listening(
+  snapshot: &Snapshot,
+  projects: &[Project],
+  base_path: &Path,
+) -> Vec<ListeningPort> {
+  let mut sockets: Vec<(String, u16, u64)> = ["/proc/net/tcp", "/proc/net/tcp6"]
+    .iter()
+    .filter_map(|path| fs::read_to_string(path).ok())
+    .flat_map(|table| parse_table(&table))
+    .collect();
+  sockets.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+  sockets.dedup_by(|a, b| a.0 == b.0 && a.1 == b.1);
+
+  let owners = socket_owners();
+  sockets
+    .into_iter()
+    .map(|(address, port, inode)| {
+      let pid = owners.get(&inode).copied();
+      let proc_dir = |pid: u32| PathBuf::from(format!("/proc/{pid}"));
+      let command = pid
+        .and_then(|pid| fs::read_to_string(proc_dir(pid).join("comm")).ok())
+        .map(|comm| comm.trim().to_string());
+      let project = pid.and_then(|pid| {
+        let cwd = fs::read_link(proc_dir(pid).join("cwd")).ok();
+        project_of(
+          pid,
+          |pid| snapshot.parent(pid),
+          cwd.as_deref(),
+          projects,
+          base_path,
+        )
+      });
+      ListeningPort {
+        port,
+        address,
+        pid,
+        command,
+        project,
+      }
+    })
+    .collect()
+}
+
+/// The listening sockets of a `/proc/net/tcp` or `/proc/net/tcp6` table as
+/// `(address, port, inode)`.
+fn parse_table(table: &str) -> Vec<(String, u16, u64)> {
+  table
+    .lines()
+    .skip(1)
+    .filter_map(|line| {
+      let fields: Vec<&str> = line.split_whitespace().collect();
+      if fields.get(3) != Some(&LISTEN) {
+        return None;
+      }
+      let (address, port) = fields.get(1)?.split_once(':')?;
+      Some((
+        parse_address(address)?,
+        u16::from_str_radix(port, 16).ok()?,
+        fields.get(9)?.parse().ok()?,
+      ))
+    })
+    .collect()
+}
+
+/// The tables print addresses as 32-bit hex words in host byte order.
+fn parse_address(hex: &str) -> Option<String> {
+  let bytes: Vec<u8> = (0..hex.len())
+    .step_by(8)
+    .map(|start| u32::from_str_radix(hex.get(start..start + 8)?, 16).ok())
+    .collect::<Option<Vec<u32>>>()?
+    .into_iter()
+    .flat_map(u32::to_ne_bytes)
+    .collect();
+  match bytes.len() {
+    4 => Some(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?).to_string()),
+    16 => Some(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?).to_string()),
+    _ => None,
+  }
+}
+
+/// The pids holding each socket inode, for the processes whose descriptors are readable.
+fn socket_owners() -> HashMap<u64, u32> {
+  let mut owners = HashMap::new();
+  for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+    let Some(pid) = entry
+      .file_name()
+      .to_str()
+      .and_then(|name| name.parse().ok())
+    else {
+      continue;
+    };
+    for fd in fs::read_dir(entry.path().join("fd"))
+      .into_iter()
+      .flatten()
+      .flatten()
+    {
+      let inode = fs::read_link(fd.path()).ok().and_then(|target| {
+        target
+          .to_str()?
+          .strip_prefix("socket:[")?
+          .strip_suffix(']')?
+          .parse()
+          .ok()
+      });
+      if let Some(inode) = inode {
+        owners.entry(inode).or_insert(pid);
+      }
+    }
+  }
+  owners
+}
+
+/// The project whose script is `pid` or one of its ancestors, else the project whose
+/// directory holds the `cwd` of `pid`, the innermost one for nested projects.
+fn project_of(
+  pid: u32,
+  parent: impl Fn(u32) -> Option<u32>,
+  cwd: Option<&Path>,
+  projects: &[Project],
+  base_path: &Path,
+) -> Option<String> {
+  let mut ancestor = Some(pid);
+  for _ in 0..MAX_DEPTH {
+    let Some(current) = ancestor else {
+      break;
+    };
+    if let Some(project) = projects.iter().find(|project| project.pid == Some(current)) {
+      return Some(project.name.clone());
+    }
+    ancestor = parent(current);
+  }
+  let cwd = cwd?;
+  projects
+    .iter()
+    .filter(|project| cwd.starts_with(base_path.join(&project.path)))
+    .max_by_key(|project| project.path.components().count())
+    .map(|project| project.name.clone())
+}
+
+/// Opens `url` in the default browser.
+pub fn open_url(url: &str) -> Result<(), String> {
+  let opener = if cfg!(target_os = "macos") {
+    "open"
+  } else {
+    "xdg-open"
+  };
+  let mut child = Command::new(opener)
+    .arg(url)
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::null())
+    .spawn()
+    .map_err(|err| format!("Failed to run {opener}: {err}"))?;
+  thread::spawn(move || child.wait());
+  Ok(())
+}
+
+/// Copies `text` with the OSC 52 escape sequence, which terminals forward to the system
+/// clipboard, also over SSH.
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+  let mut stdout = io::stdout();
+  write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))
+    .and_then(|_| stdout.flush())
+    .map_err(|err| format!("Failed to copy to the clipboard: {err}"))
+}
+
+fn base64(bytes: &[u8]) -> String {
+  const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+  let mut encoded = String::new();
+  for chunk in bytes.chunks(3) {
+    let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+      group | (*byte as u32) << (16 - 8 * i)
+    });
+    for i in 0..4 {
+      if i <= chunk.len() {
+        encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+      } else {
+        encoded.push('=');
+      }
+    }
+  }
+  encoded
+}
+
+/// The listening ports, shown instead of the output views when visible.
+#[derive(Debug, Default)]
+pub struct PortsState {
+  pub visible: bool,
+  pub ports: Vec<ListeningPort>,
+  pub selected: usize,
+}
+
+impl PortsState {
+  /// Replaces the ports, keeping the selected port selected if it still listens.
+  pub fn update(&mut self, ports: Vec<ListeningPort>) {
+    let selected = self.selected().map(|port| port.port);
+    self.selected = selected
+      .and_then(|selected| ports.iter().position(|port| port.port == selected))
+      .unwrap_or(self.selected)
+      .min(ports.len().saturating_sub(1));
+    self.ports = ports;
+  }
+
+  pub fn selected(&self) -> Option<&ListeningPort> {
+    self.ports.get(self.selected)
+  }
+
+  pub fn select_by(&mut self, delta: i32) {
+    let last = self.ports.len().saturating_sub(1) as i32;
+    self.selected = (self.selected as i32 + delta).clamp(0, last) as usize;
+  }
+
+  pub fn find(&self, port: u16) -> Option<&ListeningPort> {
+    self.ports.iter().find(|listening| listening.port == port)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn port(port: u16) -> ListeningPort {
+    ListeningPort {
+      port,
+      address: "::".to_string(),
+      pid: None,
+      command: None,
+      project: None,
+    }
+  }
+
+  #[test]
+  fn test_parse_table() {
+    let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0BB8 0100007F:D3C2 01 00000000:00000000 00:00000000 00000000  1000        0 4343 1 0000000000000000 20 4 30 10 -1
+";
+    assert_eq!(
+      parse_table(table),
+      vec![("127.0.0.1".to_string(), 3000, 4242)]
+    );
+    assert_eq!(
+      parse_address("00000000000000000000000000000000").as_deref(),
+      Some("::")
+    );
+    assert_eq!(parse_address("0BB8"), None);
+  }
+
+  #[test]
+  fn test_project_of() {
+    let project = |name: &str, path: &str, pid: Option<u32>| Project {
+      name: name.to_string(),
+      path: PathBuf::from(path),
+      pid,
+      ..Default::default()
+    };
+    let projects = vec![
+      project("web-app", "apps/web-app", Some(10)),
+      project("docs", "apps/web-app/docs", None),
+    ];
+    let parents = HashMap::from([(12, 11), (11, 10), (10, 1)]);
+    let parent = |pid| parents.get(&pid).copied();
+    let base = Path::new("/repo");
+
+    assert_eq!(
+      project_of(12, parent, None, &projects, base).as_deref(),
+      Some("web-app")
+    );
+    let cwd = Path::new("/repo/apps/web-app/docs/src");
+    assert_eq!(
+      project_of(20, parent, Some(cwd), &projects, base).as_deref(),
+      Some("docs")
+    );
+    assert_eq!(
+      project_of(20, parent, Some(Path::new("/tmp")), &projects, base),
+      None
+    );
+  }
+
+  #[test]
+  fn test_base64() {
+    assert_eq!(
+      base64(b"http://localhost:3000"),
+      "aHR0cDovL2xvY2FsaG9zdDozMDAw"
+    );
+    assert_eq!(base64(b"ab"), "YWI=");
+    assert_eq!(base64(b"a"), "YQ==");
+  }
+
+  #[test]
+  fn test_ports_state() {
+    let mut state = PortsState::default();
+    state.update(vec![port(3000), port(5173), port(8080)]);
+    state.select_by(1);
+    assert_eq!(state.selected().unwrap().port, 5173);
+    state.update(vec![port(22), port(3000), port(5173)]);
+    assert_eq!(state.selected().unwrap().port, 5173);
+    state.update(vec![port(22)]);
+    assert_eq!(state.selected().unwrap().port, 22);
+    assert_eq!(state.find(22), Some(&port(22)));
+    assert_eq!(state.find(80), None);
+  }
+}
@@ -0,0 +1,557 @@
+use std::collections::BTreeMap;
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use strum::Display;
+
+lazy_static! {
+  // `tsc` without and with `--pretty`.
+  static ref TSC: Regex = Regex::new(
+    r"^(?P<file>[^\s(][^(]*)\((?P<line>\d+),(?P<column>\d+)\): (?P<severity>error|warning) (?P<rule>TS\d+): (?P<message>.+)$"
+  )
+  .unwrap();
+  static ref TSC_PRETTY: Regex = Regex::new(
+    r"^(?P<file>\S+):(?P<line>\d+):(?P<column>\d+) - (?P<severity>error|warning) (?P<rule>TS\d+): (?P<message>.+)$"
+  )
+  .unwrap();
+  // ESLint's stylish format: a file on its own line, then one indented line per problem.
+  static ref ESLINT_STYLISH_FILE: Regex = Regex::new(r"^(?P<file>[^\s:]*[/\\][^\s]*\.\w+)$").unwrap();
+  static ref ESLINT_STYLISH: Regex = Regex::new(
+    r"^\s+(?P<line>\d+):(?P<column>\d+)\s+(?P<severity>error|warning)\s+(?P<message>.+?)(?:\s{2,}(?P<rule>[\w@/-]+))?$"
+  )
+  .unwrap();
+  static ref ESLINT_COMPACT: Regex = Regex::new(
+    r"^(?P<file>.+): line (?P<line>\d+), col (?P<column>\d+), (?P<severity>Error|Warning) - (?P<message>.+?)(?: \((?P<rule>[\w@/-]+)\))?$"
+  )
+  .unwrap();
+  // esbuild, also used by Vite: the message, then its location on a later line.
+  static ref ESBUILD: Regex = Regex::new(
+    r"^\s*[✘▲] \[(?P<severity>ERROR|WARNING)\] (?P<message>.+?)(?: \[(?P<rule>[\w-]+)\])?$"
+  )
+  .unwrap();
+  static ref ESBUILD_LOCATION: Regex =
+    Regex::new(r"^\s+(?P<file>[^\s:]+):(?P<line>\d+):(?P<column>\d+):\s*$").unwrap();
+  static ref VITE: Regex = Regex::new(r"Internal server error: (?P<message>.+)$").unwrap();
+  static ref VITE_LOCATION: Regex =
+    Regex::new(r"^\s*File: (?P<file>\S+?):(?P<line>\d+):(?P<column>\d+)").unwrap();
+  // webpack: the location, then the message on the next line.
+  static ref WEBPACK: Regex = Regex::new(
+    r"^(?P<severity>ERROR|WARNING) in (?P<file>[^\s:]+)(?::(?P<line>\d+):(?P<column>\d+)| (?P<line2>\d+):(?P<column2>\d+)(?:-\d+)?)?\s*$"
+  )
+  .unwrap();
+  // Jest: the failed test, then the first stack frame outside node_modules.
+  static ref JEST: Regex = Regex::new(r"^\s*● (?P<message>.+)$").unwrap();
+  static ref JEST_LOCATION: Regex =
+    Regex::new(r"^\s+at (?:.*\()?(?P<file>[^\s()]+):(?P<line>\d+):(?P<column>\d+)\)?$").unwrap();
+  // Lines after which earlier problems are outdated.
+  static ref RESTART: Regex = Regex::new(
+    r"^Attempting to start project:|^Rebuilding .* after file changes$|Starting compilation in watch mode|File change detected\. Starting incremental compilation"
+  )
+  .unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum ProblemSeverity {
+  Error,
+  Warning,
+}
+
+impl ProblemSeverity {
+  fn parse(raw: &str) -> Self {
+    if raw.to_lowercase().starts_with("warn") {
+      Self::Warning
+    } else {
+      Self::Error
+    }
+  }
+}
+
+/// A diagnostic a tool printed, pointing at a file of the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+  /// As printed, either absolute or relative to the project directory.
+  pub file: String,
+  pub line: u32,
+  pub column: u32,
+  pub severity: ProblemSeverity,
+  pub message: String,
+  pub rule: Option<String>,
+}
+
+/// Counts of errors and warnings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProblemCounts {
+  pub errors: usize,
+  pub warnings: usize,
+}
+
+impl ProblemCounts {
+  pub fn of<'a>(problems: impl IntoIterator<Item = &'a Problem>) -> Self {
+    let mut counts = Self::default();
+    for problem in problems {
+      match problem.severity {
+        ProblemSeverity::Error => counts.errors += 1,
+        ProblemSeverity::Warning => counts.warnings += 1,
+      }
+    }
+    counts
+  }
+}
+
+/// Problems of every project, parsed from the lines their output gains.
+#[derive(Debug, Clone, Default)]
+pub struct ProblemsState {
+  pub visible: bool,
+  /// Index into `all()` of the selected problem.
+  pub selected: usize,
+  projects: BTreeMap<String, Parser>,
+}
+
+impl ProblemsState {
+  /// The complete lines of a project's `output` that are not parsed yet, with the byte
+  /// offset they start at. Output that shrank is parsed again from the start.
+  pub fn unparsed<'a>(&self, project: &str, output: &'a str) -> (usize, &'a str) {
+    let parsed = self.projects.get(project).map_or(0, |parser| parser.parsed);
+    let parsed = if output.is_char_boundary(parsed) {
+      parsed
+    } else {
+      0
+    };
+    let end = output[parsed..]
+      .rfind('\n')
+      .map_or(parsed, |newline| parsed + newline + 1);
+    (parsed, &output[parsed..end])
+  }
+
+  /// Parses the `lines` from [`Self::unparsed`], which start at byte `offset`.
+  pub fn update(&mut self, project: &str, offset: usize, lines: &str) {
+    let parser = self.projects.entry(project.to_string()).or_default();
+    if offset != parser.parsed {
+      *parser = Parser::default();
+    }
+    parser.feed(lines);
+    parser.parsed = offset + lines.len();
+    self.selected = self.selected.min(self.all().len().saturating_sub(1));
+  }
+
+  /// Forgets the problems of the projects `keep` rejects.
+  pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+    self.projects.retain(|project, _| keep(project));
+    self.selected = self.selected.min(self.all().len().saturating_sub(1));
+  }
+
+  pub fn of(&self, project: &str) -> &[Problem] {
+    self
+      .projects
+      .get(project)
+      .map_or(&[], |parser| parser.problems.as_slice())
+  }
+
+  /// Problems of all projects, by project name and errors first.
+  pub fn all(&self) -> Vec<(&str, &Problem)> {
+    let mut all: Vec<(&str, &Problem)> = self
+      .projects
+      .iter()
+      .flat_map(|(project, parser)| {
+        parser
+          .problems
+          .iter()
+          .map(move |problem| (project.as_str(), problem))
+      })
+      .collect();
+    all.sort_by_key(|(project, problem)| (*project, problem.severity));
+    all
+  }
+
+  pub fn selected(&self) -> Option<(&str, &Problem)> {
+    self.all().get(self.selected).copied()
+  }
+
+  pub fn select_by(&mut self, delta: i32) {
+    let last = self.all().len().saturating_sub(1) as i32;
+    self.selected = (self.selected as i32 + delta).clamp(0, last) as usize;
+  }
+}
+
+/// A message whose location is printed on a later line.
+#[derive(Debug, Clone, Default)]
+enum Pending {
+  #[default]
+  None,
+  /// Problems of a file listed by ESLint's stylish formatter.
+  StylishFile(String),
+  Message {
+    severity: ProblemSeverity,
+    message: String,
+    rule: Option<String>,
+    location: &'static Regex,
+  },
+  /// A webpack location waiting for its message.
+  Location {
+    severity: ProblemSeverity,
+    file: String,
+    line: u32,
+    column: u32,
+  },
+}
+
+fn number(captures: &Captures, names: &[&str]) -> u32 {
+  names
+    .iter()
+    .find_map(|name| captures.name(name))
+    .and_then(|value| value.as_str().parse().ok())
+    .unwrap_or(1)
+}
+
+fn text(captures: &Captures, name: &str) -> String {
+  captures
+    .name(name)
+    .map_or("", |value| value.as_str())
+    .trim()
+    .to_string()
+}
+
+fn problem(captures: &Captures, file: String, severity: ProblemSeverity) -> Problem {
+  Problem {
+    file: file.trim_start_matches("./").to_string(),
+    line: number(captures, &["line", "line2"]),
+    column: number(captures, &["column", "column2"]),
+    severity,
+    message: text(captures, "message"),
+    rule: captures.name("rule").map(|rule| rule.as_str().to_string()),
+  }
+}
+
+/// The problems reported since the last (re)start in the lines of a script's output
+/// parsed so far.
+#[derive(Debug, Clone, Default)]
+struct Parser {
+  /// Bytes of the output parsed.
+  parsed: usize,
+  problems: Vec<Problem>,
+  pending: Pending,
+}
+
+impl Parser {
+  fn feed(&mut self, output: &str) {
+    for line in output.lines() {
+      let line = strip_ansi_escapes::strip_str(line);
+      let line = line.trim_end();
+      if RESTART.is_match(line) {
+        self.problems.clear();
+        self.pending = Pending::None;
+        continue;
+      }
+
+      let (found, next) = parse_line(line, std::mem::take(&mut self.pending));
+      self.pending = next;
+      if let Some(found) = found {
+        if !self.problems.contains(&found) {
+          self.problems.push(found);
+        }
+      }
+    }
+  }
+}
+
+fn starts_problem(line: &str) -> bool {
+  [
+    &*TSC,
+    &*TSC_PRETTY,
+    &*ESLINT_COMPACT,
+    &*WEBPACK,
+    &*ESBUILD,
+    &*VITE,
+    &*JEST,
+  ]
+  .iter()
+  .any(|regex| regex.is_match(line))
+}
+
+fn parse_line(line: &str, pending: Pending) -> (Option<Problem>, Pending) {
+  match pending {
+    Pending::StylishFile(file) => {
+      if let Some(captures) = ESLINT_STYLISH.captures(line) {
+        let severity = ProblemSeverity::parse(&text(&captures, "severity"));
+        return (
+          Some(problem(&captures, file.clone(), severity)),
+          Pending::StylishFile(file),
+        );
+      }
+    }
+    Pending::Message {
+      severity,
+      message,
+      rule,
+      location,
+    } => {
+      if let Some(captures) = location.captures(line) {
+        let mut found = problem(&captures, text(&captures, "file"), severity);
+        found.message = message;
+        found.rule = rule;
+        return (Some(found), Pending::None);
+      }
+      // Messages are followed by code frames or stack traces before their location.
+      if !starts_problem(line) {
+        return (
+          None,
+          Pending::Message {
+            severity,
+            message,
+            rule,
+            location,
+          },
+        );
+      }
+    }
+    Pending::Location {
+      severity,
+      file,
+      line: line_number,
+      column,
+    } => {
+      if line.trim().is_empty() {
+        return (
+          None,
+          Pending::Location {
+            severity,
+            file,
+            line: line_number,
+            column,
+          },
+        );
+      }
+      let found = Problem {
+        file: file.trim_start_matches("./").to_string(),
+        line: line_number,
+        column,
+        severity,
+        message: line.trim().to_string(),
+        rule: None,
+      };
+      return (Some(found), Pending::None);
+    }
+    Pending::None => {}
+  }
+
+  for regex in [&*TSC, &*TSC_PRETTY, &*ESLINT_COMPACT] {
+    if let Some(captures) = regex.captures(line) {
+      let severity = ProblemSeverity::parse(&text(&captures, "severity"));
+      return (
+        Some(problem(&captures, text(&captures, "file"), severity)),
+        Pending::None,
+      );
+    }
+  }
+  if let Some(captures) = WEBPACK.captures(line) {
+    return (
+      None,
+      Pending::Location {
+        severity: ProblemSeverity::parse(&text(&captures, "severity")),
+        file: text(&captures, "file"),
+        line: number(&captures, &["line", "line2"]),
+        column: number(&captures, &["column", "column2"]),
+      },
+    );
+  }
+  let message = |captures: &Captures, severity, location| Pending::Message {
+    severity,
+    message: text(captures, "message"),
+    rule: captures.name("rule").map(|rule| rule.as_str().to_string()),
+    location,
+  };
+  if let Some(captures) = ESBUILD.captures(line) {
+    let severity = ProblemSeverity::parse(&text(&captures, "severity"));
+    return (None, message(&captures, severity, &ESBUILD_LOCATION));
+  }
+  if let Some(captures) = VITE.captures(line) {
+    return (
+      None,
+      message(&captures, ProblemSeverity::Error, &VITE_LOCATION),
+    );
+  }
+  if let Some(captures) = JEST.captures(line) {
+    return (
+      None,
+      message(&captures, ProblemSeverity::Error, &JEST_LOCATION),
+    );
+  }
+  if let Some(captures) = ESLINT_STYLISH_FILE.captures(line) {
+    return (None, Pending::StylishFile(text(&captures, "file")));
+  }
+  (None, Pending::None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(output: &str) -> Vec<Problem> {
+    let mut parser = Parser::default();
+    parser.feed(output);
+    parser.problems
+  }
+
+  fn update(state: &mut ProblemsState, project: &str, output: &str) {
+    let (offset, lines) = state.unparsed(project, output);
+    state.update(project, offset, lines);
+  }
+
+  fn locations(output: &str) -> Vec<(String, u32, u32, ProblemSeverity, Option<String>)> {
+    parse(output)
+      .into_iter()
+      .map(|problem| {
+        (
+          problem.file,
+          problem.line,
+          problem.column,
+          problem.severity,
+          problem.rule,
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn test_tsc() {
+    let output =
+      "src/Button.tsx(42,7): error TS2322: Type 'string' is not assignable to type 'number'.\n\
+                  src/App.tsx:3:1 - warning TS6133: 'React' is declared but never used.";
+    assert_eq!(
+      locations(output),
+      vec![
+        (
+          "src/Button.tsx".to_string(),
+          42,
+          7,
+          ProblemSeverity::Error,
+          Some("TS2322".to_string())
+        ),
+        (
+          "src/App.tsx".to_string(),
+          3,
+          1,
+          ProblemSeverity::Warning,
+          Some("TS6133".to_string())
+        ),
+      ]
+    );
+    assert_eq!(
+      parse(output)[0].message,
+      "Type 'string' is not assignable to type 'number'."
+    );
+  }
+
+  #[test]
+  fn test_eslint() {
+    let stylish = "\n/repo/apps/web/src/App.tsx\n  \
+                   3:10  error    'x' is assigned a value but never used  no-unused-vars\n  \
+                   7:1   warning  Unexpected console statement            no-console\n\n\
+                   ✖ 2 problems (1 error, 1 warning)";
+    let problems = parse(stylish);
+    assert_eq!(problems.len(), 2);
+    assert_eq!(problems[0].file, "/repo/apps/web/src/App.tsx");
+    assert_eq!(
+      problems[0].message,
+      "'x' is assigned a value but never used"
+    );
+    assert_eq!(problems[1].rule.as_deref(), Some("no-console"));
+
+    let compact = "/repo/src/a.js: line 2, col 5, Error - Missing semicolon. (semi)";
+    assert_eq!(
+      locations(compact),
+      vec![(
+        "/repo/src/a.js".to_string(),
+        2,
+        5,
+        ProblemSeverity::Error,
+        Some("semi".to_string())
+      )]
+    );
+  }
+
+  #[test]
+  fn test_bundlers() {
+    let esbuild = "✘ [ERROR] Could not resolve \"./Missing\"\n\n    src/index.ts:1:7:\n      1 │ import x from \"./Missing\";";
+    let problems = parse(esbuild);
+    assert_eq!(problems[0].file, "src/index.ts");
+    assert_eq!(problems[0].message, "Could not resolve \"./Missing\"");
+
+    let webpack = "ERROR in ./src/index.ts 12:4-10\nModule not found: Error: Can't resolve './x'";
+    assert_eq!(
+      parse(webpack)[0],
+      Problem {
+        file: "src/index.ts".to_string(),
+        line: 12,
+        column: 4,
+        severity: ProblemSeverity::Error,
+        message: "Module not found: Error: Can't resolve './x'".to_string(),
+        rule: None,
+      }
+    );
+
+    let vite = "[vite] Internal server error: Unexpected token\n  Plugin: vite:esbuild\n  File: /repo/src/main.tsx:8:3";
+    assert_eq!(locations(vite)[0].0, "/repo/src/main.tsx");
+  }
+
+  #[test]
+  fn test_state() {
+    let mut state = ProblemsState::default();
+    let web_app = "src/a.ts(1,1): warning TS6133: unused\n";
+    update(&mut state, "web-app", web_app);
+    update(
+      &mut state,
+      "api",
+      "src/b.ts(2,2): error TS2304: Cannot find name 'x'.\n",
+    );
+    let web_app = format!("{web_app}src/c.ts(3,3): error TS1005: ';' expected.\nsrc/d.ts(4,");
+    update(&mut state, "web-app", &web_app);
+    assert_eq!(
+      state.unparsed("web-app", &web_app),
+      (web_app.len() - 11, "")
+    );
+    let all = state.all();
+    assert_eq!(
+      all
+        .iter()
+        .map(|(project, problem)| (*project, problem.line))
+        .collect::<Vec<_>>(),
+      vec![("api", 2), ("web-app", 3), ("web-app", 1)]
+    );
+    assert_eq!(
+      ProblemCounts::of(state.of("web-app")),
+      ProblemCounts {
+        errors: 1,
+        warnings: 1
+      }
+    );
+
+    state.select_by(5);
+    assert_eq!(state.selected().map(|(_, problem)| problem.line), Some(1));
+    update(
+      &mut state,
+      "web-app",
+      &format!("{web_app}4): error TS1005: ';' expected.\nAttempting to start project: web-app\n"),
+    );
+    assert_eq!(state.selected, 0);
+    assert!(state.of("web-app").is_empty());
+    update(
+      &mut state,
+      "web-app",
+      "src/a.ts(1,1): warning TS6133: unused\n",
+    );
+    assert_eq!(state.of("web-app").len(), 1);
+  }
+
+  #[test]
+  fn test_jest_and_restart() {
+    let jest = "  ● Button › renders a label\n\n    expect(received).toBe(expected)\n\n      at Object.<anonymous> (src/Button.test.tsx:12:23)";
+    let problems = parse(jest);
+    assert_eq!(problems[0].message, "Button › renders a label");
+    assert_eq!((problems[0].line, problems[0].column), (12, 23));
+
+    let restarted = format!("{jest}\nAttempting to start project: web-app\n");
+    assert!(parse(&restarted).is_empty());
+  }
+}
@@ -1,16 +1,20 @@
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Serialize};
-use std::io::{BufRead, BufReader};
 use std::os::unix::net::UnixStream;
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
 use std::{env, fs};
 use strum::Display;
-use sysinfo::System;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
-use crate::action::{ProjectCommand, ProjectScript};
+use crate::action::ProjectCommand;
+use crate::pty::Pty;
+use crate::sampler::ProcessSampler;
+
+pub const WORKSPACE_ENV: &str = "TEXUS_MONOREPO_PATH";
+pub const PACKAGE_MANAGER: &str = "pnpm";
 
 #[derive(Default, Serialize, Deserialize, PartialEq, Eq, Clone, Display, Debug, Copy)]
 pub enum ProjectStatus {
@@ -26,11 +30,25 @@ pub enum ProjectStatus {
 #[derive(Default, Clone, Debug)]
 pub struct Project {
   pub name: String,
+  /// Directory of the project relative to the workspace root.
+  pub path: PathBuf,
   pub status: ProjectStatus,
   pub dependencies: Vec<String>,
   pub commands: Vec<String>,
+  /// The `keywords` of package.json.
+  pub tags: Vec<String>,
   pub output: Arc<Mutex<String>>,
   pub pid: Option<u32>,
+  /// The terminal of the script started last.
+  pub pty: Option<Pty>,
+}
+
+/// The parts of a package.json texus cares about.
+#[derive(Default, Clone, Debug)]
+pub struct PackageManifest {
+  pub dependencies: Vec<String>,
+  pub commands: Vec<String>,
+  pub keywords: Vec<String>,
 }
 
 impl Serialize for Project {
@@ -38,11 +56,13 @@ impl Serialize for Project {
   where
     S: serde::Serializer,
   {
-    let mut state = serializer.serialize_struct("Project", 5)?;
+    let mut state = serializer.serialize_struct("Project", 6)?;
     state.serialize_field("name", &self.name)?;
+    state.serialize_field("path", &self.path)?;
     state.serialize_field("status", &self.status)?;
     state.serialize_field("dependencies", &self.dependencies)?;
     state.serialize_field("commands", &self.commands)?;
+    state.serialize_field("tags", &self.tags)?;
     state.end()
   }
 }
@@ -50,45 +70,65 @@ impl Serialize for Project {
 impl Project {
   pub fn new(
     name: String,
-    dependencies: Vec<String>,
-    commands: Vec<String>,
+    path: PathBuf,
+    manifest: PackageManifest,
     status: ProjectStatus,
     output: Arc<Mutex<String>>,
     pid: Option<u32>,
   ) -> Self {
     Self {
       name,
-      dependencies,
-      commands,
+      path,
+      dependencies: manifest.dependencies,
+      commands: manifest.commands,
+      tags: manifest.keywords,
       status,
       output,
       pid,
+      pty: None,
     }
   }
 }
 
+/// Progress of a workspace scan.
+#[derive(Debug)]
+pub enum ScanEvent {
+  /// The number of directories to read.
+  Started(usize),
+  /// A directory was read, holding a project unless it has no readable `package.json`.
+  Scanned(Option<Project>),
+}
+
 #[derive(Default, Debug)]
 pub struct ProjectManager {
   pub base_path: PathBuf,
+  /// The running processes, shared with the components that check pids.
+  pub sampler: ProcessSampler,
 }
 
 impl ProjectManager {
   pub fn default() -> Self {
-    dotenvy::from_path(".env").ok();
-    if let Ok(local_path) = env::var("TEXUS_MONOREPO_PATH") {
+    if let Some(base_path) = Self::workspace_root() {
       Self {
-        base_path: PathBuf::from(local_path),
+        base_path,
+        sampler: ProcessSampler::default(),
       }
     } else {
-      panic!("TEXUS_MONOREPO_PATH variable not found in .env");
+      panic!("{WORKSPACE_ENV} variable not found in .env");
     }
   }
 
+  /// The monorepo root from `TEXUS_MONOREPO_PATH`, read from the environment or `.env`.
+  pub fn workspace_root() -> Option<PathBuf> {
+    dotenvy::from_path(".env").ok();
+    env::var(WORKSPACE_ENV).ok().map(PathBuf::from)
+  }
+
   fn read_file(path: &PathBuf) -> Option<String> {
     fs::read_to_string(path).ok()
   }
 
-  fn parse_package_json(package_json: &str) -> (Vec<String>, Vec<String>) {
+  fn parse_package_json(package_json: &str) -> PackageManifest {
     let package_json: serde_json::Value = serde_json::from_str(package_json).unwrap_or_default();
 
     let dependencies = package_json["dependencies"]
@@ -101,10 +141,24 @@ impl ProjectManager {
       .map(|scripts| scripts.keys().map(String::from).collect())
       .unwrap_or_default();
 
-    (dependencies, commands)
+    let keywords = package_json["keywords"]
+      .as_array()
+      .map(|keywords| {
+        keywords
+          .iter()
+          .filter_map(|keyword| keyword.as_str().map(String::from))
+          .collect()
+      })
+      .unwrap_or_default();
+
+    PackageManifest {
+      dependencies,
+      commands,
+      keywords,
+    }
   }
 
-  fn terminate_process(pid: u32) -> Result<(), String> {
+  pub fn terminate_process(pid: u32) -> Result<(), String> {
     #[cfg(unix)]
     {
       let result = Command::new("kill")
@@ -120,77 +174,63 @@ impl ProjectManager {
     }
   }
 
-  //? Refactor
+  /// Whether the project's script runs, or its pid was running at the last process sample.
   pub fn is_running(&self, project: &Project) -> bool {
-    let mut sys = System::new_all();
-    let mut is_run = false;
-    sys.refresh_all();
-
-    if let Some(pid) = project.pid {
-      is_run = sys.process(sysinfo::Pid::from(pid as usize)).is_some();
-    }
-
-    if project.pid.is_some() && is_run {
-      true
-    } else {
-      false
-    }
+    project.pty.as_ref().is_some_and(Pty::is_running)
+      || project
+        .pid
+        .is_some_and(|pid| self.sampler.snapshot().is_running(pid))
   }
 
-  //? REFACTOR: this all function is doing too much and we can change with third party library for better performance, readability and multiplatform support
-  ///! and fix it: We can't close after running at the moment
-  pub fn check_running(path: &PathBuf) -> (ProjectStatus, Option<u32>) {
-    let sock_path = path.join("webpack.sock");
-
-    if fs::metadata(&sock_path).is_ok() {
-      if let Ok(stream) = UnixStream::connect(&sock_path) {
-        drop(stream);
-
-        // Use ps to find node process in the directory
-        if let Ok(output) = Command::new("ps").args(["aux"]).output() {
-          let output_str = String::from_utf8_lossy(&output.stdout);
-          let path_str = path.to_str().unwrap_or_default();
-
-          if let Some(line) = output_str
-            .lines()
-            .find(|line| line.contains("node") && line.contains(path_str))
-          {
-            let pid = line
-              .split_whitespace()
-              .nth(1)
-              .and_then(|pid_str| pid_str.parse::<u32>().ok());
-
-            tracing::info!("Found running process: {:?}", pid);
-
-            return (ProjectStatus::Running, Some(pid.unwrap()));
-          }
+  //? REFACTOR: we can change with third party library for better performance, readability and multiplatform support
+  //? and fix it: We can't close after running at the moment
+  /// Detects dev servers already running in `paths`, listing the processes at most once for
+  /// all of them.
+  pub fn check_running(paths: &[PathBuf]) -> Vec<(ProjectStatus, Option<u32>)> {
+    let mut processes: Option<String> = None;
+    paths
+      .iter()
+      .map(|path| {
+        let sock_path = path.join("webpack.sock");
+        if fs::metadata(&sock_path).is_err() || UnixStream::connect(&sock_path).is_err() {
+          return (ProjectStatus::Idle, None);
         }
 
-        return (ProjectStatus::Running, None);
-      }
-    }
-    (ProjectStatus::Idle, None)
+        // Use ps to find node process in the directory
+        let processes = processes.get_or_insert_with(|| {
+          Command::new("ps")
+            .args(["aux"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+            .unwrap_or_default()
+        });
+        let path_str = path.to_str().unwrap_or_default();
+        let pid = processes
+          .lines()
+          .find(|line| line.contains("node") && line.contains(path_str))
+          .and_then(|line| line.split_whitespace().nth(1)?.parse::<u32>().ok());
+
+        tracing::info!("Found running process: {:?}", pid);
+        (ProjectStatus::Running, pid)
+      })
+      .collect()
   }
 
-  pub fn execute_script(
-    &mut self,
-    project: &mut Project,
-    cmd: &ProjectScript,
-  ) -> mpsc::Receiver<String> {
-    let project_path = self.base_path.join(&project.name);
+  pub fn execute_script(&mut self, project: &mut Project, script: &str) -> mpsc::Receiver<String> {
+    let project_path = self.base_path.join(&project.path);
     let package_json_path = project_path.join("package.json");
 
     let (tx, rx) = mpsc::channel();
 
     let shared_output = Arc::new(Mutex::new(String::new()));
     let output_clone = Arc::clone(&shared_output);
-    let package_manager = "pnpm";
+    let package_manager = PACKAGE_MANAGER;
 
     if let Some(content) = Self::read_file(&package_json_path) {
-      let (dependencies, commands) = Self::parse_package_json(&content);
+      let manifest = Self::parse_package_json(&content);
 
-      if !commands.contains(&cmd.to_string()) {
-        let error_msg = format!("No '{}' command found in package.json", cmd);
+      if !manifest.commands.iter().any(|command| command == script) {
+        let error_msg = format!("No '{}' command found in package.json", script);
         tx.send(error_msg.clone()).unwrap();
         let mut output = output_clone.lock().unwrap();
         output.push_str(&error_msg);
@@ -199,8 +239,9 @@ impl ProjectManager {
         return rx;
       }
 
-      project.dependencies = dependencies;
-      project.commands = commands;
+      project.dependencies = manifest.dependencies;
+      project.commands = manifest.commands;
+      project.tags = manifest.keywords;
     } else {
       let error_msg = "Failed to read package.json".to_string();
       tx.send(error_msg.clone()).unwrap();
@@ -214,84 +255,26 @@ impl ProjectManager {
     let project_name = project.name.clone();
     project.status = ProjectStatus::Running;
 
-    let command_type = cmd.clone();
-    thread::spawn(move || {
-      let initial_msg = format!("Attempting to start project: {}", project_name);
-      tx.send(initial_msg.clone()).unwrap();
-
-      {
-        let mut output = output_clone.lock().unwrap();
-        output.push_str(&initial_msg);
-        output.push('\n');
+    let initial_msg = format!("Attempting to start project: {}", project_name);
+    tx.send(initial_msg).unwrap();
+
+    // Scripts run in a pseudo-terminal, so that watchers and prompts can be used interactively.
+    match Pty::spawn(package_manager, &[script], &project_path) {
+      Ok((pty, child, reader)) => {
+        project.pid = child.process_id();
+        project.pty = Some(pty.clone());
+        pty.forward(child, reader, tx, move |exit| {
+          format!(
+            "Project {} finished with status: {}",
+            project_name, exit.reason
+          )
+        });
       }
-
-      let mut command = Command::new(package_manager);
-      command
-        .current_dir(&project_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-      match command_type {
-        ProjectScript::Start => {
-          command.arg("start");
-        }
-        ProjectScript::Build => {
-          command.arg("build");
-        }
-      }
-
-      match command.spawn() {
-        Ok(mut child) => {
-          if let Some(stdout) = child.stdout.take() {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines().map_while(Result::ok) {
-              tx.send(line.clone()).unwrap();
-              let mut output = output_clone.lock().unwrap();
-              output.push_str(&line);
-              output.push('\n');
-            }
-          }
-
-          if let Some(stderr) = child.stderr.take() {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines().map_while(Result::ok) {
-              tx.send(line.clone()).unwrap();
-
-              let mut output = output_clone.lock().unwrap();
-              output.push_str(&line);
-              output.push('\n');
-            }
-          }
-
-          match child.wait() {
-            Ok(status) => {
-              let status_msg = format!("Project {} finished with status: {}", project_name, status);
-              tx.send(status_msg.clone()).unwrap();
-
-              let mut output = output_clone.lock().unwrap();
-              output.push_str(&status_msg);
-              output.push('\n');
-            }
-            Err(e) => {
-              let error_msg = format!("Error waiting for project {}: {}", project_name, e);
-              tx.send(error_msg.clone()).unwrap();
-
-              let mut output = output_clone.lock().unwrap();
-              output.push_str(&error_msg);
-              output.push('\n');
-            }
-          }
-        }
-        Err(e) => {
-          let error_msg = format!("Failed to start project {}: {}", project_name, e);
-          tx.send(error_msg.clone()).unwrap();
-
-          let mut output = output_clone.lock().unwrap();
-          output.push_str(&error_msg);
-          output.push('\n');
-        }
+      Err(e) => {
+        let error_msg = format!("Failed to start project {}: {}", project_name, e);
+        tx.send(error_msg).unwrap();
       }
-    });
+    }
 
     thread::spawn(move || loop {
       std::thread::sleep(std::time::Duration::from_millis(100));
@@ -301,9 +284,6 @@ impl ProjectManager {
   }
 
   pub fn execute_command(&self, project: &mut Project, cmd: &ProjectCommand) {
-    let project_path = self.base_path.join(&project.name);
-    Self::check_running(&project_path);
-
     match cmd {
       ProjectCommand::Stop => {
         if self.is_running(project) {
@@ -329,32 +309,46 @@ impl ProjectManager {
     }
   }
 
-  pub fn get_projects(&self) -> Vec<Project> {
-    fs::read_dir(&self.base_path)
-      .into_iter()
-      .flat_map(|entries| entries.filter_map(Result::ok))
-      .filter_map(|entry| {
-        let path = entry.path();
-        if path.is_dir() {
-          let package_json_path = path.join("package.json");
-          if let Some(package_json_content) = Self::read_file(&package_json_path) {
-            let (dependencies, commands) = Self::parse_package_json(&package_json_content);
-            let (status, pid) = Self::check_running(&path);
-
-            tracing::info!("found pid {:?}", pid);
-
-            return Some(Project::new(
-              path.file_name()?.to_str()?.to_string(),
-              dependencies,
-              commands,
-              status,
-              Arc::new(Mutex::new(String::new())),
-              pid,
-            ));
-          }
+  /// Reads the workspace on a blocking task, sending the projects as they are found. The
+  /// channel closes once every directory was read.
+  pub fn scan(&self) -> UnboundedReceiver<ScanEvent> {
+    let (tx, rx) = unbounded_channel();
+    let base_path = self.base_path.clone();
+    tokio::task::spawn_blocking(move || {
+      let dirs: Vec<PathBuf> = fs::read_dir(&base_path)
+        .into_iter()
+        .flat_map(|entries| entries.filter_map(Result::ok))
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+      let _ = tx.send(ScanEvent::Started(dirs.len()));
+
+      let running = Self::check_running(&dirs);
+      for (path, (status, pid)) in dirs.iter().zip(running) {
+        let project = Self::read_project(&base_path, path, status, pid);
+        if tx.send(ScanEvent::Scanned(project)).is_err() {
+          return;
         }
-        None
-      })
-      .collect()
+      }
+    });
+    rx
+  }
+
+  fn read_project(
+    base_path: &Path,
+    path: &Path,
+    status: ProjectStatus,
+    pid: Option<u32>,
+  ) -> Option<Project> {
+    let package_json_content = Self::read_file(&path.join("package.json"))?;
+    let manifest = Self::parse_package_json(&package_json_content);
+    Some(Project::new(
+      path.file_name()?.to_str()?.to_string(),
+      path.strip_prefix(base_path).ok()?.to_path_buf(),
+      manifest,
+      status,
+      Arc::new(Mutex::new(String::new())),
+      pid,
+    ))
   }
 }
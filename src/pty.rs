@@ -0,0 +1,360 @@
+use std::{
+  fmt,
+  io::{Read, Write},
+  path::Path,
+  sync::{mpsc::Sender, Arc, Mutex},
+  thread,
+};
+
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use ratatui::{
+  crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+  style::{Color, Modifier, Style},
+  text::{Line, Span},
+};
+
+/// Size scripts start with, until the output panel they are shown in is drawn.
+const INITIAL_SIZE: (u16, u16) = (24, 80);
+
+/// A started script: its terminal, its process and the reader of its output.
+pub type Spawned = (Pty, Box<dyn Child + Send + Sync>, Box<dyn Read + Send>);
+
+/// How the process in a terminal ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Exit {
+  pub success: bool,
+  /// E.g. "Exited with code 1".
+  pub reason: String,
+}
+
+impl Exit {
+  /// Waits for `child` to exit.
+  pub fn wait(child: &mut dyn Child) -> Self {
+    match child.wait() {
+      Ok(status) => Self {
+        success: status.success(),
+        reason: status.to_string(),
+      },
+      Err(err) => Self {
+        success: false,
+        reason: format!("Failed to wait for the process: {err}"),
+      },
+    }
+  }
+}
+
+/// A pseudo-terminal a script runs in, with the screen it draws.
+#[derive(Clone)]
+pub struct Pty {
+  parser: Arc<Mutex<vt100::Parser>>,
+  writer: Arc<Mutex<Box<dyn Write + Send>>>,
+  master: Arc<Mutex<Box<dyn MasterPty + Send>>>,
+  exit: Arc<Mutex<Option<Exit>>>,
+}
+
+impl fmt::Debug for Pty {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Pty")
+      .field("running", &self.is_running())
+      .finish_non_exhaustive()
+  }
+}
+
+impl Pty {
+  /// Starts `program` in a new pseudo-terminal; its output is read from the returned reader.
+  pub fn spawn(program: &str, args: &[&str], cwd: &Path) -> Result<Spawned, String> {
+    let (rows, cols) = INITIAL_SIZE;
+    let pair = native_pty_system()
+      .openpty(PtySize {
+        rows,
+        cols,
+        ..Default::default()
+      })
+      .map_err(|err| err.to_string())?;
+    let mut command = CommandBuilder::new(program);
+    command.args(args);
+    command.cwd(cwd);
+    let child = pair
+      .slave
+      .spawn_command(command)
+      .map_err(|err| err.to_string())?;
+    let reader = pair
+      .master
+      .try_clone_reader()
+      .map_err(|err| err.to_string())?;
+    let writer = pair.master.take_writer().map_err(|err| err.to_string())?;
+    let pty = Self {
+      parser: Arc::new(Mutex::new(vt100::Parser::new(rows, cols, 0))),
+      writer: Arc::new(Mutex::new(writer)),
+      master: Arc::new(Mutex::new(pair.master)),
+      exit: Default::default(),
+    };
+    Ok((pty, child, reader))
+  }
+
+  /// Feeds the output of `child` to the screen and sends it as log lines until it exits, then
+  /// sends the line `finished` makes of the exit and records it.
+  pub fn forward(
+    self,
+    mut child: Box<dyn Child + Send + Sync>,
+    mut reader: Box<dyn Read + Send>,
+    tx: Sender<String>,
+    finished: impl FnOnce(&Exit) -> String + Send + 'static,
+  ) {
+    thread::spawn(move || {
+      let mut lines = LineSplitter::default();
+      let mut buffer = [0; 4096];
+      // Reading fails instead of ending once the process and its children exit.
+      while let Ok(read @ 1..) = reader.read(&mut buffer) {
+        self.process(&buffer[..read]);
+        for line in lines.push(&buffer[..read]) {
+          let _ = tx.send(line);
+        }
+      }
+      if let Some(line) = lines.finish() {
+        let _ = tx.send(line);
+      }
+
+      let exit = Exit::wait(child.as_mut());
+      let _ = tx.send(finished(&exit));
+      self.set_exited(exit);
+    });
+  }
+
+  /// Feeds output of the process to the screen.
+  pub fn process(&self, bytes: &[u8]) {
+    self.parser.lock().unwrap().process(bytes);
+  }
+
+  pub fn is_running(&self) -> bool {
+    self.exit().is_none()
+  }
+
+  pub fn exit(&self) -> Option<Exit> {
+    self.exit.lock().unwrap().clone()
+  }
+
+  pub fn set_exited(&self, exit: Exit) {
+    *self.exit.lock().unwrap() = Some(exit);
+  }
+
+  /// Sends a key to the process, encoded the way a terminal would.
+  pub fn send_key(&self, key: KeyEvent) -> Result<(), String> {
+    let application_cursor = self.parser.lock().unwrap().screen().application_cursor();
+    let bytes = key_to_bytes(key, application_cursor);
+    let mut writer = self.writer.lock().unwrap();
+    writer
+      .write_all(&bytes)
+      .and_then(|_| writer.flush())
+      .map_err(|err| format!("Failed to send input: {err}"))
+  }
+
+  /// Resizes the terminal to the area it is shown in, if that changed.
+  pub fn resize(&self, rows: u16, cols: u16) {
+    let mut parser = self.parser.lock().unwrap();
+    if parser.screen().size() == (rows, cols) || rows == 0 || cols == 0 {
+      return;
+    }
+    parser.set_size(rows, cols);
+    let size = PtySize {
+      rows,
+      cols,
+      ..Default::default()
+    };
+    if let Err(err) = self.master.lock().unwrap().resize(size) {
+      tracing::warn!("Failed to resize terminal: {err}");
+    }
+  }
+
+  /// The screen as styled lines, and the cursor position unless hidden.
+  pub fn screen(&self) -> (Vec<Line<'static>>, Option<(u16, u16)>) {
+    let parser = self.parser.lock().unwrap();
+    let screen = parser.screen();
+    let (rows, cols) = screen.size();
+    let lines = (0..rows)
+      .map(|row| {
+        let mut spans: Vec<Span> = Vec::new();
+        let mut text = String::new();
+        let mut style = Style::default();
+        for col in 0..cols {
+          let Some(cell) = screen.cell(row, col) else {
+            continue;
+          };
+          if cell.is_wide_continuation() {
+            continue;
+          }
+          let cell_style = cell_style(cell);
+          if cell_style != style && !text.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut text), style));
+          }
+          style = cell_style;
+          match cell.contents() {
+            contents if contents.is_empty() => text.push(' '),
+            contents => text.push_str(&contents),
+          }
+        }
+        spans.push(Span::styled(text, style));
+        Line::from(spans)
+      })
+      .collect();
+    let cursor = (!screen.hide_cursor()).then(|| screen.cursor_position());
+    (lines, cursor)
+  }
+}
+
+fn color(color: vt100::Color) -> Option<Color> {
+  match color {
+    vt100::Color::Default => None,
+    vt100::Color::Idx(index) => Some(Color::Indexed(index)),
+    vt100::Color::Rgb(r, g, b) => Some(Color::Rgb(r, g, b)),
+  }
+}
+
+fn cell_style(cell: &vt100::Cell) -> Style {
+  let mut style = Style::default();
+  if let Some(fg) = color(cell.fgcolor()) {
+    style = style.fg(fg);
+  }
+  if let Some(bg) = color(cell.bgcolor()) {
+    style = style.bg(bg);
+  }
+  for (enabled, modifier) in [
+    (cell.bold(), Modifier::BOLD),
+    (cell.italic(), Modifier::ITALIC),
+    (cell.underline(), Modifier::UNDERLINED),
+    (cell.inverse(), Modifier::REVERSED),
+  ] {
+    if enabled {
+      style = style.add_modifier(modifier);
+    }
+  }
+  style
+}
+
+/// The bytes a terminal sends for `key`; arrows differ in application cursor mode.
+pub fn key_to_bytes(key: KeyEvent, application_cursor: bool) -> Vec<u8> {
+  let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+  let alt = key.modifiers.contains(KeyModifiers::ALT);
+  let arrow = |code: u8| {
+    if application_cursor {
+      vec![0x1b, b'O', code]
+    } else {
+      vec![0x1b, b'[', code]
+    }
+  };
+  let bytes = match key.code {
+    KeyCode::Char(c) if ctrl && c.is_ascii_alphabetic() => {
+      vec![c.to_ascii_lowercase() as u8 & 0x1f]
+    }
+    KeyCode::Char(' ') if ctrl => vec![0],
+    KeyCode::Char(c) => c.to_string().into_bytes(),
+    KeyCode::Enter => vec![b'\r'],
+    KeyCode::Tab => vec![b'\t'],
+    KeyCode::BackTab => b"\x1b[Z".to_vec(),
+    KeyCode::Backspace => vec![0x7f],
+    KeyCode::Esc => vec![0x1b],
+    KeyCode::Up => arrow(b'A'),
+    KeyCode::Down => arrow(b'B'),
+    KeyCode::Right => arrow(b'C'),
+    KeyCode::Left => arrow(b'D'),
+    KeyCode::Home => arrow(b'H'),
+    KeyCode::End => arrow(b'F'),
+    KeyCode::Insert => b"\x1b[2~".to_vec(),
+    KeyCode::Delete => b"\x1b[3~".to_vec(),
+    KeyCode::PageUp => b"\x1b[5~".to_vec(),
+    KeyCode::PageDown => b"\x1b[6~".to_vec(),
+    KeyCode::F(n @ 1..=4) => vec![0x1b, b'O', b'P' + n - 1],
+    KeyCode::F(n @ 5..=12) => {
+      let code = [15, 17, 18, 19, 20, 21, 23, 24][n as usize - 5];
+      format!("\x1b[{code}~").into_bytes()
+    }
+    _ => Vec::new(),
+  };
+  if alt && !bytes.is_empty() {
+    [vec![0x1b], bytes].concat()
+  } else {
+    bytes
+  }
+}
+
+/// Splits terminal output into plain log lines, dropping escape sequences and lines that
+/// were overwritten with a carriage return, like progress bars.
+#[derive(Debug, Default)]
+pub struct LineSplitter {
+  pending: Vec<u8>,
+}
+
+impl LineSplitter {
+  /// The lines completed by `bytes`.
+  pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+    self.pending.extend_from_slice(bytes);
+    let Some(end) = self.pending.iter().rposition(|&byte| byte == b'\n') else {
+      return Vec::new();
+    };
+    let complete: Vec<u8> = self.pending.drain(..end).collect();
+    self.pending.remove(0);
+    complete
+      .split(|&byte| byte == b'\n')
+      .map(Self::clean)
+      .collect()
+  }
+
+  /// The last line, if the output did not end with a newline.
+  pub fn finish(&mut self) -> Option<String> {
+    let rest = Self::clean(&std::mem::take(&mut self.pending));
+    (!rest.is_empty()).then_some(rest)
+  }
+
+  fn clean(line: &[u8]) -> String {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim_end_matches('\r');
+    let last = line.rsplit('\r').next().unwrap_or_default();
+    strip_ansi_escapes::strip_str(last)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_key_to_bytes() {
+    let key = |code, modifiers| KeyEvent::new(code, modifiers);
+    assert_eq!(
+      key_to_bytes(key(KeyCode::Char('r'), KeyModifiers::NONE), false),
+      b"r"
+    );
+    assert_eq!(
+      key_to_bytes(key(KeyCode::Char('c'), KeyModifiers::CONTROL), false),
+      vec![3]
+    );
+    assert_eq!(
+      key_to_bytes(key(KeyCode::Char('b'), KeyModifiers::ALT), false),
+      b"\x1bb"
+    );
+    assert_eq!(
+      key_to_bytes(key(KeyCode::Up, KeyModifiers::NONE), false),
+      b"\x1b[A"
+    );
+    assert_eq!(
+      key_to_bytes(key(KeyCode::Up, KeyModifiers::NONE), true),
+      b"\x1bOA"
+    );
+    assert_eq!(
+      key_to_bytes(key(KeyCode::F(5), KeyModifiers::NONE), false),
+      b"\x1b[15~"
+    );
+  }
+
+  #[test]
+  fn test_line_splitter() {
+    let mut splitter = LineSplitter::default();
+    assert!(splitter.push(b"\x1b[32mready\x1b[0m in 120").is_empty());
+    assert_eq!(
+      splitter.push(b" ms\r\nbuilding 10%\rbuilding 100%\r\n\x1b[2J"),
+      vec!["ready in 120 ms", "building 100%"]
+    );
+    assert_eq!(splitter.push(b"press h for help"), Vec::<String>::new());
+    assert_eq!(splitter.finish(), Some("press h for help".to_string()));
+  }
+}
@@ -0,0 +1,193 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, VariantNames};
+
+use crate::{config::ProjectConfig, pty::Exit};
+
+/// Longest wait between automatic restarts.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// When a script that exited is started again.
+#[derive(
+  Debug,
+  Default,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Display,
+  EnumString,
+  VariantNames,
+  Serialize,
+  Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum RestartPolicy {
+  #[default]
+  Never,
+  /// Only after exiting with an error.
+  OnFailure,
+  /// After any exit, e.g. for servers that should keep running.
+  Always,
+}
+
+/// Restarts of one project's script and why it last exited.
+#[derive(Debug, Clone, Default)]
+pub struct RestartState {
+  /// The script to start again.
+  pub script: Option<String>,
+  /// Restarts since the script was last started by hand.
+  pub restarts: u32,
+  pub last_exit: Option<Exit>,
+  /// When the exited script is started again, if it is.
+  pub restart_at: Option<Instant>,
+  /// Whether the script is started again by hand once the stopped run exits.
+  pub restart_on_exit: bool,
+  /// Whether the current run is watched for its exit; cleared when it is stopped by hand.
+  watching: bool,
+}
+
+impl RestartState {
+  /// Starts watching a run of `script`; runs started by hand reset the restart count.
+  pub fn started(&mut self, script: &str, by_hand: bool) {
+    self.script = Some(script.to_string());
+    self.watching = true;
+    self.restart_at = None;
+    self.restart_on_exit = false;
+    if by_hand {
+      self.restarts = 0;
+    }
+  }
+
+  /// Stops watching, so that stopping the script by hand does not restart it.
+  pub fn stopped(&mut self) {
+    self.watching = false;
+    self.restart_at = None;
+    self.restart_on_exit = false;
+  }
+
+  /// Starts `script` again once the stopped run exits and frees its ports.
+  pub fn restart_after_exit(&mut self, script: &str) {
+    self.script = Some(script.to_string());
+    self.restart_on_exit = true;
+  }
+
+  /// The script restarted by hand, once the run it waited for exited.
+  pub fn due_after_exit(&mut self) -> Option<String> {
+    if std::mem::take(&mut self.restart_on_exit) {
+      return self.script.clone();
+    }
+    None
+  }
+
+  /// Records the exit of a watched run and schedules a restart as `config` says.
+  pub fn exited(&mut self, exit: Exit, config: &ProjectConfig, now: Instant) {
+    if !self.watching {
+      return;
+    }
+    self.watching = false;
+    let restart = match config.restart_policy {
+      RestartPolicy::Never => false,
+      RestartPolicy::OnFailure => !exit.success,
+      RestartPolicy::Always => true,
+    };
+    self.last_exit = Some(exit);
+    if restart && self.restarts < config.max_restarts {
+      self.restart_at = Some(now + backoff(config.restart_delay, self.restarts));
+    }
+  }
+
+  /// The script to restart now, if its backoff has passed.
+  pub fn due(&mut self, now: Instant) -> Option<String> {
+    if self.restart_at.is_some_and(|at| at <= now) {
+      self.restart_at = None;
+      self.restarts += 1;
+      return self.script.clone();
+    }
+    None
+  }
+}
+
+/// The delay before restart number `restarts + 1`, doubling from `delay` milliseconds.
+pub fn backoff(delay: u64, restarts: u32) -> Duration {
+  Duration::from_millis(delay)
+    .saturating_mul(2u32.saturating_pow(restarts))
+    .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn exit(success: bool) -> Exit {
+    Exit {
+      success,
+      reason: if success {
+        "Success".to_string()
+      } else {
+        "Exited with code 1".to_string()
+      },
+    }
+  }
+
+  #[test]
+  fn test_backoff() {
+    assert_eq!(backoff(1000, 0), Duration::from_secs(1));
+    assert_eq!(backoff(1000, 3), Duration::from_secs(8));
+    assert_eq!(backoff(1000, 30), MAX_BACKOFF);
+  }
+
+  #[test]
+  fn test_restart_on_failure() {
+    let config = ProjectConfig {
+      restart_policy: RestartPolicy::OnFailure,
+      max_restarts: 2,
+      restart_delay: 1000,
+      ..Default::default()
+    };
+    let now = Instant::now();
+    let mut state = RestartState::default();
+    state.started("dev", true);
+    state.exited(exit(false), &config, now);
+    assert_eq!(state.due(now), None);
+    assert_eq!(
+      state.due(now + Duration::from_secs(1)),
+      Some("dev".to_string())
+    );
+
+    state.started("dev", false);
+    state.exited(exit(false), &config, now);
+    assert_eq!(state.restart_at, Some(now + Duration::from_secs(2)));
+    state.due(now + Duration::from_secs(2));
+    state.started("dev", false);
+    state.exited(exit(false), &config, now);
+    assert_eq!(state.restarts, 2);
+    assert_eq!(state.restart_at, None);
+    assert_eq!(state.last_exit, Some(exit(false)));
+
+    state.started("dev", true);
+    state.exited(exit(true), &config, now);
+    assert_eq!(state.restart_at, None);
+
+    state.started("dev", true);
+    state.stopped();
+    state.exited(exit(false), &config, now);
+    assert_eq!(state.restart_at, None);
+  }
+
+  #[test]
+  fn test_restart_after_exit() {
+    let mut state = RestartState::default();
+    state.started("dev", true);
+    state.stopped();
+    state.restart_after_exit("build");
+    assert_eq!(state.due_after_exit(), Some("build".to_string()));
+    assert_eq!(state.due_after_exit(), None);
+
+    state.restart_after_exit("dev");
+    state.stopped();
+    assert_eq!(state.due_after_exit(), None);
+  }
+}
@@ -0,0 +1,276 @@
+use std::{
+  collections::{HashMap, VecDeque},
+  sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex, RwLock, Weak,
+  },
+  thread,
+  time::Duration,
+};
+
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::action::Action;
+
+/// Samples kept for the sparklines of each project.
+const HISTORY_LEN: usize = 60;
+
+/// CPU and memory use of a process, or of a process tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+  /// Percent of one core, so busy trees can go over 100.
+  pub cpu: f32,
+  /// Resident memory in bytes.
+  pub memory: u64,
+}
+
+impl Usage {
+  /// E.g. `12% 340M`.
+  pub fn summary(&self) -> String {
+    format!("{:.0}% {}", self.cpu, format_bytes(self.memory))
+  }
+}
+
+/// `bytes` in the largest unit that keeps a number above 1, e.g. `340M` or `4.1G`.
+pub fn format_bytes(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["B", "K", "M", "G"];
+  let mut value = bytes as f64;
+  let mut unit = 0;
+  while value >= 1024.0 && unit < UNITS.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+  if value < 10.0 && unit > 0 {
+    format!("{value:.1}{}", UNITS[unit])
+  } else {
+    format!("{value:.0}{}", UNITS[unit])
+  }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Process {
+  parent: Option<u32>,
+  usage: Usage,
+}
+
+/// The processes running when the sampler last looked.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+  processes: HashMap<u32, Process>,
+}
+
+impl Snapshot {
+  /// Refreshes the processes of `system` with their CPU and memory use. CPU use is measured
+  /// since the previous refresh of the same `system`.
+  fn sample(system: &mut System) -> Self {
+    system.refresh_processes_specifics(
+      ProcessesToUpdate::All,
+      true,
+      ProcessRefreshKind::new().with_cpu().with_memory(),
+    );
+    // Linux lists threads too, sharing the memory of their process.
+    let processes = system
+      .processes()
+      .iter()
+      .filter(|(_, process)| process.thread_kind().is_none())
+      .map(|(pid, process)| {
+        let process = Process {
+          parent: process.parent().map(|parent| parent.as_u32()),
+          usage: Usage {
+            cpu: process.cpu_usage(),
+            memory: process.memory(),
+          },
+        };
+        (pid.as_u32(), process)
+      })
+      .collect();
+    Self { processes }
+  }
+
+  pub fn is_running(&self, pid: u32) -> bool {
+    self.processes.contains_key(&pid)
+  }
+
+  pub fn parent(&self, pid: u32) -> Option<u32> {
+    self.processes.get(&pid)?.parent
+  }
+
+  /// The summed use of `pid` and its descendants, e.g. pnpm, node and the dev server it starts.
+  pub fn tree_usage(&self, pid: u32) -> Option<Usage> {
+    let mut total = self.processes.get(&pid)?.usage;
+    let mut stack = vec![pid];
+    while let Some(parent) = stack.pop() {
+      for (child, process) in &self.processes {
+        if process.parent == Some(parent) && *child != parent {
+          total.cpu += process.usage.cpu;
+          total.memory += process.usage.memory;
+          stack.push(*child);
+        }
+      }
+    }
+    Some(total)
+  }
+
+  /// Removes the pids in `tracked` that stopped running and returns them. Pids map to whether
+  /// an earlier sample saw them, so that those started after this sample are not reported.
+  fn exited(&self, tracked: &mut HashMap<u32, bool>) -> Vec<u32> {
+    let mut exited = Vec::new();
+    tracked.retain(|pid, seen| {
+      if self.is_running(*pid) {
+        *seen = true;
+      } else if *seen {
+        exited.push(*pid);
+        return false;
+      }
+      true
+    });
+    exited
+  }
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+  snapshot: RwLock<Arc<Snapshot>>,
+  tracked: Mutex<HashMap<u32, bool>>,
+  interval_ms: AtomicU64,
+  started: AtomicBool,
+}
+
+/// Lists the running processes on a background thread, so that checking a pid does not scan
+/// the whole system. Clones share the same snapshot.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessSampler {
+  shared: Arc<Shared>,
+}
+
+impl ProcessSampler {
+  /// Starts sampling every `interval`, sending `Action::ProcessExited` for tracked pids that
+  /// disappear and `Action::ProcessesSampled` after each sample. Later calls only change the
+  /// interval. The thread stops with the last clone.
+  pub fn start(&self, interval: Duration, action_tx: UnboundedSender<Action>) {
+    self
+      .shared
+      .interval_ms
+      .store(interval.as_millis() as u64, Ordering::Relaxed);
+    if self.shared.started.swap(true, Ordering::Relaxed) {
+      return;
+    }
+    let shared: Weak<Shared> = Arc::downgrade(&self.shared);
+    thread::spawn(move || {
+      let mut system = System::new();
+      while let Some(shared) = shared.upgrade() {
+        let snapshot = Snapshot::sample(&mut system);
+        let exited = snapshot.exited(&mut shared.tracked.lock().unwrap());
+        *shared.snapshot.write().unwrap() = Arc::new(snapshot);
+        for pid in exited {
+          let _ = action_tx.send(Action::ProcessExited(pid));
+        }
+        let _ = action_tx.send(Action::ProcessesSampled);
+        let interval = Duration::from_millis(shared.interval_ms.load(Ordering::Relaxed));
+        drop(shared);
+        thread::sleep(interval);
+      }
+    });
+  }
+
+  /// The processes found by the last sample.
+  pub fn snapshot(&self) -> Arc<Snapshot> {
+    self.shared.snapshot.read().unwrap().clone()
+  }
+
+  /// Reports when `pid` exits.
+  pub fn track(&self, pid: u32) {
+    self.shared.tracked.lock().unwrap().entry(pid).or_default();
+  }
+}
+
+/// Recent use of a running project, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct UsageHistory {
+  samples: VecDeque<Usage>,
+}
+
+impl UsageHistory {
+  pub fn push(&mut self, usage: Usage) {
+    if self.samples.len() == HISTORY_LEN {
+      self.samples.pop_front();
+    }
+    self.samples.push_back(usage);
+  }
+
+  pub fn latest(&self) -> Option<Usage> {
+    self.samples.back().copied()
+  }
+
+  /// CPU use in whole percents, for a sparkline.
+  pub fn cpu(&self) -> Vec<u64> {
+    self
+      .samples
+      .iter()
+      .map(|usage| usage.cpu.round() as u64)
+      .collect()
+  }
+
+  /// Memory use in bytes, for a sparkline.
+  pub fn memory(&self) -> Vec<u64> {
+    self.samples.iter().map(|usage| usage.memory).collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn process(pid: u32, parent: Option<u32>, cpu: f32, memory: u64) -> (u32, Process) {
+    let usage = Usage { cpu, memory };
+    (pid, Process { parent, usage })
+  }
+
+  #[test]
+  fn test_snapshot() {
+    let snapshot = Snapshot::sample(&mut System::new());
+    assert!(snapshot.is_running(std::process::id()));
+
+    let snapshot = Snapshot {
+      processes: HashMap::from([process(1, None, 0.0, 0), process(2, Some(1), 0.0, 0)]),
+    };
+    let mut tracked = HashMap::from([(2, false), (3, true), (4, false)]);
+    assert_eq!(snapshot.exited(&mut tracked), vec![3]);
+    assert_eq!(tracked, HashMap::from([(2, true), (4, false)]));
+  }
+
+  #[test]
+  fn test_tree_usage() {
+    let snapshot = Snapshot {
+      processes: HashMap::from([
+        process(1, None, 1.0, 100),
+        process(10, Some(1), 2.0, 200),
+        process(11, Some(10), 30.0, 3000),
+        process(12, Some(10), 40.0, 4000),
+        process(20, Some(1), 50.0, 5000),
+      ]),
+    };
+    let usage = snapshot.tree_usage(10).unwrap();
+    assert_eq!(usage.cpu, 72.0);
+    assert_eq!(usage.memory, 7200);
+    assert_eq!(snapshot.tree_usage(20).unwrap().memory, 5000);
+    assert_eq!(snapshot.tree_usage(30), None);
+  }
+
+  #[test]
+  fn test_usage_history() {
+    assert_eq!(format_bytes(512), "512B");
+    assert_eq!(format_bytes(340 * 1024 * 1024), "340M");
+    assert_eq!(format_bytes(4400 * 1024 * 1024), "4.3G");
+
+    let mut history = UsageHistory::default();
+    for memory in 0..HISTORY_LEN as u64 + 2 {
+      history.push(Usage { cpu: 1.4, memory });
+    }
+    assert_eq!(history.memory().len(), HISTORY_LEN);
+    assert_eq!(history.memory()[0], 2);
+    assert_eq!(history.cpu()[0], 1);
+    assert_eq!(history.latest().unwrap().summary(), "1% 61B");
+  }
+}
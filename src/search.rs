@@ -0,0 +1,283 @@
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString, VariantNames};
+
+use crate::project_manager::Project;
+
+/// Qualifiers understood in project searches, e.g. `status:running` or `dep:react`.
+pub const QUALIFIERS: [&str; 5] = ["status", "dep", "script", "tag", "path"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Qualifier {
+  pub key: String,
+  pub value: String,
+}
+
+/// A project search split into fuzzy text and `key:value` qualifiers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+  pub text: String,
+  pub qualifiers: Vec<Qualifier>,
+}
+
+impl Query {
+  pub fn parse(raw: &str) -> Self {
+    let mut text = Vec::new();
+    let mut qualifiers = Vec::new();
+    for word in raw.split_whitespace() {
+      match word.split_once(':') {
+        Some((key, value)) if QUALIFIERS.contains(&key) && !value.is_empty() => {
+          qualifiers.push(Qualifier {
+            key: key.to_string(),
+            value: value.to_lowercase(),
+          })
+        }
+        _ => text.push(word),
+      }
+    }
+    Self {
+      text: text.join(" "),
+      qualifiers,
+    }
+  }
+
+  fn matches_qualifiers(&self, project: &Project) -> bool {
+    let contains = |values: &[String], value: &str| {
+      values
+        .iter()
+        .any(|candidate| candidate.to_lowercase().contains(value))
+    };
+    self
+      .qualifiers
+      .iter()
+      .all(|Qualifier { key, value }| match key.as_str() {
+        "status" => project.status.to_string().to_lowercase() == *value,
+        "dep" => contains(&project.dependencies, value),
+        "script" => contains(&project.commands, value),
+        "tag" => contains(&project.tags, value),
+        "path" => project
+          .path
+          .to_string_lossy()
+          .to_lowercase()
+          .starts_with(value.as_str()),
+        _ => true,
+      })
+  }
+}
+
+/// Orders of the project list.
+#[derive(
+  Debug,
+  Default,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Display,
+  EnumString,
+  VariantNames,
+  Serialize,
+  Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ProjectSort {
+  /// Workspace order, or best match first while searching.
+  #[default]
+  None,
+  /// Highest CPU use first, then the projects that do not run.
+  Cpu,
+  /// Most memory first, then the projects that do not run.
+  Memory,
+}
+
+/// A project that matched a search, with the character indices of its name that matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+  pub index: usize,
+  pub score: i64,
+  pub indices: Vec<usize>,
+}
+
+/// Filters `projects` by `query`, best fuzzy matches first.
+pub fn search(projects: &[Project], query: &Query) -> Vec<Match> {
+  let matcher = SkimMatcherV2::default().smart_case();
+  let mut matches: Vec<Match> = projects
+    .iter()
+    .enumerate()
+    .filter(|(_, project)| query.matches_qualifiers(project))
+    .filter_map(|(index, project)| {
+      if query.text.is_empty() {
+        return Some(Match {
+          index,
+          score: 0,
+          indices: Vec::new(),
+        });
+      }
+      let (score, indices) = matcher.fuzzy_indices(&project.name, &query.text)?;
+      Some(Match {
+        index,
+        score,
+        indices,
+      })
+    })
+    .collect();
+  // Stable, so equally good matches keep the workspace order.
+  matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+  matches
+}
+
+/// Ranks `candidates` by how well they fuzzy-match `pattern`, best first.
+pub fn rank<'a>(candidates: impl IntoIterator<Item = &'a str>, pattern: &str) -> Vec<Match> {
+  let matcher = SkimMatcherV2::default().smart_case();
+  let mut matches: Vec<Match> = candidates
+    .into_iter()
+    .enumerate()
+    .filter_map(|(index, candidate)| {
+      let (score, indices) = matcher.fuzzy_indices(candidate, pattern)?;
+      Some(Match {
+        index,
+        score,
+        indices,
+      })
+    })
+    .collect();
+  matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+  matches
+}
+
+/// Search inputs that keep separate histories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchKind {
+  Projects,
+  Help,
+}
+
+/// Previously submitted queries of one search input, navigated like a shell history.
+#[derive(Debug, Clone, Default)]
+pub struct History {
+  entries: Vec<String>,
+  cursor: Option<usize>,
+}
+
+impl History {
+  pub fn push(&mut self, entry: &str) {
+    self.cursor = None;
+    if entry.is_empty() {
+      return;
+    }
+    self.entries.retain(|existing| existing != entry);
+    self.entries.push(entry.to_string());
+  }
+
+  /// Moves to the next older entry.
+  pub fn previous(&mut self) -> Option<&str> {
+    let cursor = match self.cursor {
+      Some(cursor) => cursor.checked_sub(1)?,
+      None => self.entries.len().checked_sub(1)?,
+    };
+    self.cursor = Some(cursor);
+    self.entries.get(cursor).map(String::as_str)
+  }
+
+  /// Moves to the next newer entry, or past the newest one to an empty input.
+  pub fn next(&mut self) -> Option<&str> {
+    let cursor = self.cursor? + 1;
+    if cursor >= self.entries.len() {
+      self.cursor = None;
+      return Some("");
+    }
+    self.cursor = Some(cursor);
+    self.entries.get(cursor).map(String::as_str)
+  }
+
+  /// Entries from oldest to newest.
+  pub fn entries(&self) -> &[String] {
+    &self.entries
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::*;
+  use crate::project_manager::ProjectStatus;
+
+  fn project(name: &str, path: &str, dependencies: &[&str], tags: &[&str]) -> Project {
+    Project {
+      name: name.to_string(),
+      path: PathBuf::from(path),
+      dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+      tags: tags.iter().map(|t| t.to_string()).collect(),
+      commands: vec!["start".to_string(), "storybook".to_string()],
+      status: ProjectStatus::Idle,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_parse_query() {
+    let query = Query::parse("web status:Running dep:react foo:bar app");
+    assert_eq!(query.text, "web foo:bar app");
+    assert_eq!(
+      query.qualifiers,
+      vec![
+        Qualifier {
+          key: "status".to_string(),
+          value: "running".to_string()
+        },
+        Qualifier {
+          key: "dep".to_string(),
+          value: "react".to_string()
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn test_search_ranks_and_highlights() {
+    let projects = vec![
+      project("api-mock", "apps/api-mock", &["express"], &[]),
+      project("web-app", "apps/web-app", &["react"], &["frontend"]),
+      project("ui-kit", "packages/ui-kit", &["react"], &["frontend"]),
+    ];
+    let matches = search(&projects, &Query::parse("wa"));
+    assert_eq!(matches[0].index, 1);
+    assert_eq!(matches[0].indices, vec![0, 4]);
+
+    assert_eq!(indices_of(&projects, "dep:react"), vec![1, 2]);
+    assert_eq!(
+      indices_of(&projects, "tag:frontend path:packages/"),
+      vec![2]
+    );
+    assert_eq!(indices_of(&projects, "script:story"), vec![0, 1, 2]);
+    assert!(indices_of(&projects, "status:running").is_empty());
+
+    let mut projects = projects;
+    projects[0].status = ProjectStatus::Running;
+    assert_eq!(indices_of(&projects, "status:running"), vec![0]);
+  }
+
+  fn indices_of(projects: &[Project], query: &str) -> Vec<usize> {
+    search(projects, &Query::parse(query))
+      .into_iter()
+      .map(|m| m.index)
+      .collect()
+  }
+
+  #[test]
+  fn test_history() {
+    let mut history = History::default();
+    assert_eq!(history.previous(), None);
+    history.push("web");
+    history.push("api");
+    history.push("web");
+    assert_eq!(history.previous(), Some("web"));
+    assert_eq!(history.previous(), Some("api"));
+    assert_eq!(history.previous(), None);
+    assert_eq!(history.next(), Some("web"));
+    assert_eq!(history.next(), Some(""));
+    assert_eq!(history.next(), None);
+  }
+}
@@ -0,0 +1,106 @@
+use std::{env, io::Read, path::Path, thread};
+
+use crate::pty::{Exit, Pty};
+
+/// A shell started in a project directory.
+#[derive(Debug, Clone)]
+pub struct ShellTab {
+  /// Name of the project the shell started in.
+  pub project: String,
+  pub pty: Pty,
+}
+
+impl ShellTab {
+  /// Starts `$SHELL`, or `sh` when it is not set, in `dir`.
+  pub fn open(project: &str, dir: &Path) -> Result<Self, String> {
+    let shell = env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+    let (pty, mut child, mut reader) =
+      Pty::spawn(&shell, &[], dir).map_err(|err| format!("Failed to start {shell}: {err}"))?;
+    let screen = pty.clone();
+    thread::spawn(move || {
+      let mut buffer = [0; 4096];
+      while let Ok(read @ 1..) = reader.read(&mut buffer) {
+        screen.process(&buffer[..read]);
+      }
+      screen.set_exited(Exit::wait(child.as_mut()));
+    });
+    Ok(Self {
+      project: project.to_string(),
+      pty,
+    })
+  }
+}
+
+/// Shells shown as tabs in a pane that replaces the dashboard while visible.
+#[derive(Debug, Default)]
+pub struct ShellState {
+  pub visible: bool,
+  pub tabs: Vec<ShellTab>,
+  pub current: usize,
+}
+
+impl ShellState {
+  pub fn push(&mut self, tab: ShellTab) {
+    self.tabs.push(tab);
+    self.current = self.tabs.len() - 1;
+    self.visible = true;
+  }
+
+  pub fn current(&self) -> Option<&ShellTab> {
+    self.tabs.get(self.current)
+  }
+
+  pub fn select_by(&mut self, delta: i32) {
+    let len = self.tabs.len() as i32;
+    if len > 0 {
+      self.current = (self.current as i32 + delta).rem_euclid(len) as usize;
+    }
+  }
+
+  /// Closes the tabs whose shell exited, and the pane with the last one.
+  pub fn remove_exited(&mut self) {
+    let before = self.tabs.len();
+    self.tabs.retain(|tab| tab.pty.is_running());
+    if self.tabs.len() == before {
+      return;
+    }
+    self.current = self.current.min(self.tabs.len().saturating_sub(1));
+    if self.tabs.is_empty() {
+      self.visible = false;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn exit() -> Exit {
+    Exit {
+      success: true,
+      reason: String::new(),
+    }
+  }
+
+  #[test]
+  fn test_tabs() {
+    let mut state = ShellState::default();
+    let dir = env::temp_dir();
+    state.push(ShellTab::open("web-app", &dir).unwrap());
+    state.push(ShellTab::open("api", &dir).unwrap());
+    assert!(state.visible);
+    assert_eq!(state.current().unwrap().project, "api");
+    state.select_by(1);
+    assert_eq!(state.current().unwrap().project, "web-app");
+    state.select_by(-1);
+    assert_eq!(state.current, 1);
+
+    state.tabs[1].pty.set_exited(exit());
+    state.remove_exited();
+    assert_eq!(state.tabs.len(), 1);
+    assert_eq!(state.current, 0);
+    state.tabs[0].pty.set_exited(exit());
+    state.remove_exited();
+    assert!(!state.visible);
+  }
+}
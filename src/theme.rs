@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use ratatui::style::{Color, Style};
+
+use crate::{
+  app::Mode,
+  config::{parse_style, Config},
+};
+
+pub const DEFAULT_THEME: &str = "dark";
+
+/// Built-in themes as `(name, [(style key, style string)])`, using the `parse_style` syntax.
+const THEMES: &[(&str, &[(&str, &str)])] = &[
+  (
+    "dark",
+    &[
+      ("base", ""),
+      ("border.active", "#7ec10e"),
+      ("border.inactive", "white"),
+      ("scrollbar", "#ff6100"),
+      ("list.running", "#7ec10e"),
+      ("list.idle", "white"),
+      ("list.selected", "bold inverse #00a3e1 on color11"),
+      ("list.match", "bold underline yellow"),
+      ("hint.start", "bold green"),
+      ("hint.kill", "bold red"),
+      ("hint.build", "bold blue"),
+      ("key", "bold yellow"),
+      ("popup.border", "yellow"),
+      ("toast.info", "green"),
+      ("toast.error", "bold red"),
+      ("problem.error", "bold red"),
+      ("problem.warning", "yellow"),
+      ("usage.cpu", "cyan"),
+      ("usage.memory", "magenta"),
+      ("logo", "#f8be75"),
+    ],
+  ),
+  (
+    "light",
+    &[
+      ("base", "#202020 on #fafafa"),
+      ("border.active", "#1b5e20"),
+      ("border.inactive", "#606060"),
+      ("scrollbar", "#d84315"),
+      ("list.running", "#2e7d32"),
+      ("list.idle", "#202020"),
+      ("list.selected", "bold #ffffff on #1565c0"),
+      ("list.match", "bold underline #d84315"),
+      ("hint.start", "bold #2e7d32"),
+      ("hint.kill", "bold #c62828"),
+      ("hint.build", "bold #1565c0"),
+      ("key", "bold #6a1b9a"),
+      ("popup.border", "#6a1b9a"),
+      ("toast.info", "#2e7d32"),
+      ("toast.error", "bold #c62828"),
+      ("problem.error", "bold #c62828"),
+      ("problem.warning", "#ef6c00"),
+      ("usage.cpu", "#00838f"),
+      ("usage.memory", "#6a1b9a"),
+      ("logo", "#b26a00"),
+    ],
+  ),
+  (
+    "high-contrast",
+    &[
+      ("base", "#ffffff on #000000"),
+      ("border.active", "bold #ffff00"),
+      ("border.inactive", "#ffffff"),
+      ("scrollbar", "#ffff00"),
+      ("list.running", "bold #00ff00"),
+      ("list.idle", "#ffffff"),
+      ("list.selected", "bold #000000 on #ffff00"),
+      ("list.match", "bold underline #ff00ff"),
+      ("hint.start", "bold #00ff00"),
+      ("hint.kill", "bold #ff0000"),
+      ("hint.build", "bold #00ffff"),
+      ("key", "bold #ffff00"),
+      ("popup.border", "#ffffff"),
+      ("toast.info", "bold #00ff00"),
+      ("toast.error", "bold #ff0000"),
+      ("problem.error", "bold #ff0000"),
+      ("problem.warning", "bold #ffff00"),
+      ("usage.cpu", "#00ffff"),
+      ("usage.memory", "#ff00ff"),
+      ("logo", "bold #ffff00"),
+    ],
+  ),
+  (
+    "solarized",
+    &[
+      ("base", "#839496 on #002b36"),
+      ("border.active", "#859900"),
+      ("border.inactive", "#586e75"),
+      ("scrollbar", "#cb4b16"),
+      ("list.running", "#859900"),
+      ("list.idle", "#839496"),
+      ("list.selected", "bold #fdf6e3 on #268bd2"),
+      ("list.match", "bold underline #cb4b16"),
+      ("hint.start", "bold #859900"),
+      ("hint.kill", "bold #dc322f"),
+      ("hint.build", "bold #268bd2"),
+      ("key", "bold #b58900"),
+      ("popup.border", "#b58900"),
+      ("toast.info", "#859900"),
+      ("toast.error", "bold #dc322f"),
+      ("problem.error", "bold #dc322f"),
+      ("problem.warning", "#b58900"),
+      ("usage.cpu", "#2aa198"),
+      ("usage.memory", "#d33682"),
+      ("logo", "#b58900"),
+    ],
+  ),
+];
+
+/// Colors telling projects apart in log headers and prefixes, picked by name.
+const PROJECT_COLORS: [Color; 8] = [
+  Color::Cyan,
+  Color::Magenta,
+  Color::Yellow,
+  Color::Green,
+  Color::Blue,
+  Color::LightRed,
+  Color::LightCyan,
+  Color::LightMagenta,
+];
+
+/// A style that colors `name` the same way every time.
+pub fn project_style(name: &str) -> Style {
+  // FNV-1a, so the color stays the same between runs.
+  let hash = name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+    (hash ^ byte as u32).wrapping_mul(0x01000193)
+  });
+  Style::default().fg(PROJECT_COLORS[hash as usize % PROJECT_COLORS.len()])
+}
+
+/// Named widget styles resolved from a built-in theme and the user's `styles` overrides.
+#[derive(Clone, Debug, Default)]
+pub struct Theme {
+  styles: HashMap<String, Style>,
+}
+
+impl Theme {
+  pub fn names() -> impl Iterator<Item = &'static str> {
+    THEMES.iter().map(|(name, _)| *name)
+  }
+
+  /// The style keys every built-in theme defines.
+  pub fn style_keys() -> impl Iterator<Item = &'static str> {
+    THEMES[0].1.iter().map(|(key, _)| *key)
+  }
+
+  /// Resolves the theme `name`, layering the `Home` styles from `config` on top.
+  pub fn new(name: &str, config: &Config) -> Option<Self> {
+    let (_, builtin) = THEMES.iter().find(|(theme, _)| *theme == name)?;
+    let mut styles: HashMap<String, Style> = builtin
+      .iter()
+      .map(|(key, style)| (key.to_string(), parse_style(style)))
+      .collect();
+    if let Some(overrides) = config.styles.get(&Mode::Home) {
+      styles.extend(overrides.iter().map(|(key, style)| (key.clone(), *style)));
+    }
+    Some(Self { styles })
+  }
+
+  /// Resolves the theme selected in `config`, falling back to the default theme.
+  pub fn from_config(config: &Config) -> Self {
+    Self::new(&config.config.theme, config)
+      .or_else(|| Self::new(DEFAULT_THEME, config))
+      .unwrap_or_default()
+  }
+
+  pub fn style(&self, key: &str) -> Style {
+    self.styles.get(key).copied().unwrap_or_default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_builtin_themes_define_the_same_keys() {
+    let keys = |name: &str| {
+      let (_, styles) = THEMES.iter().find(|(theme, _)| *theme == name).unwrap();
+      let mut keys: Vec<&str> = styles.iter().map(|(key, _)| *key).collect();
+      keys.sort();
+      keys
+    };
+    for name in Theme::names() {
+      assert_eq!(keys(name), keys(DEFAULT_THEME), "theme `{name}`");
+    }
+  }
+
+  #[test]
+  fn test_user_styles_override_theme() {
+    let mut config = Config::default();
+    config.styles.insert(
+      Mode::Home,
+      HashMap::from([("scrollbar".to_string(), parse_style("red"))]),
+    );
+    let theme = Theme::new("light", &config).unwrap();
+    assert_eq!(theme.style("scrollbar"), parse_style("red"));
+    assert!(Theme::new("unknown", &config).is_none());
+  }
+}
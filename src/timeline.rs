@@ -0,0 +1,139 @@
+use std::{
+  collections::VecDeque,
+  sync::{Arc, Mutex},
+  time::{Duration, Instant},
+};
+
+use crate::log_view::LogView;
+
+/// Lines kept in the timeline; older ones are dropped first.
+const CAPACITY: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineLine {
+  /// Time since the timeline was created.
+  pub at: Duration,
+  pub project: String,
+  pub text: String,
+  /// `text` in lowercase, for filtering without lowercasing every line on each draw.
+  lowercase: String,
+}
+
+/// Output lines of every project in the order they arrived, shared with the output forwarders.
+#[derive(Debug, Clone)]
+pub struct Timeline {
+  started: Instant,
+  lines: Arc<Mutex<VecDeque<TimelineLine>>>,
+}
+
+impl Default for Timeline {
+  fn default() -> Self {
+    Self {
+      started: Instant::now(),
+      lines: Default::default(),
+    }
+  }
+}
+
+impl Timeline {
+  pub fn push(&self, project: &str, text: &str) {
+    let line = TimelineLine {
+      at: self.started.elapsed(),
+      project: project.to_string(),
+      text: text.to_string(),
+      lowercase: text.to_lowercase(),
+    };
+    let mut lines = self.lines.lock().unwrap();
+    if lines.len() == CAPACITY {
+      lines.pop_front();
+    }
+    lines.push_back(line);
+  }
+
+  /// Calls `f` with the lines of `projects` (all if empty) containing the `lowercase` text.
+  /// The output forwarders wait for `f`, so it should only look at the lines it shows.
+  pub fn with_lines<R>(
+    &self,
+    projects: &[String],
+    lowercase: &str,
+    f: impl FnOnce(&[&TimelineLine]) -> R,
+  ) -> R {
+    let lines = self.lines.lock().unwrap();
+    let matching: Vec<&TimelineLine> = lines
+      .iter()
+      .filter(|line| projects.is_empty() || projects.contains(&line.project))
+      .filter(|line| lowercase.is_empty() || line.lowercase.contains(lowercase))
+      .collect();
+    f(&matching)
+  }
+}
+
+/// What the merged timeline view shows and where it is scrolled to.
+#[derive(Debug, Clone, Default)]
+pub struct TimelineState {
+  pub visible: bool,
+  /// Projects to show, or all of them when empty.
+  pub projects: Vec<String>,
+  /// Text the shown lines contain, ignoring case.
+  pub filter: String,
+  filter_lowercase: String,
+  pub view: LogView,
+  pub timeline: Timeline,
+}
+
+impl TimelineState {
+  pub fn toggle_project(&mut self, project: &str) {
+    match self.projects.iter().position(|name| name == project) {
+      Some(index) => {
+        self.projects.remove(index);
+      }
+      None => self.projects.push(project.to_string()),
+    }
+  }
+
+  pub fn set_filter(&mut self, filter: String) {
+    self.filter_lowercase = filter.to_lowercase();
+    self.filter = filter;
+  }
+
+  /// Calls `f` with the lines of the shown projects matching the filter.
+  pub fn with_lines<R>(&self, f: impl FnOnce(&[&TimelineLine]) -> R) -> R {
+    self
+      .timeline
+      .with_lines(&self.projects, &self.filter_lowercase, f)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_filter_lines() {
+    let mut state = TimelineState::default();
+    state.timeline.push("web-app", "compiled successfully");
+    state.timeline.push("api-mock", "listening on 3001");
+    state.timeline.push("web-app", "ERROR in ./src/index.ts");
+
+    let projects = |lines: &[&TimelineLine]| -> Vec<String> {
+      lines.iter().map(|line| line.project.clone()).collect()
+    };
+    assert_eq!(
+      state.with_lines(projects),
+      vec!["web-app", "api-mock", "web-app"]
+    );
+    assert!(state.with_lines(|lines| lines.windows(2).all(|pair| pair[0].at <= pair[1].at)));
+
+    state.toggle_project("web-app");
+    state.set_filter("Error".to_string());
+    assert_eq!(
+      state.with_lines(|lines| lines[0].text.clone()),
+      "ERROR in ./src/index.ts"
+    );
+    assert_eq!(state.with_lines(|lines| lines.len()), 1);
+
+    state.toggle_project("web-app");
+    state.set_filter(String::new());
+    assert_eq!(state.with_lines(|lines| lines.len()), 3);
+  }
+}
@@ -1,11 +1,43 @@
-use ratatui::{layout::Rect, Frame};
+use ratatui::{layout::Rect, style::Style, text::Span, Frame};
 
 use crate::components::home::AppState;
 
+pub mod command_line;
+pub mod help;
+pub mod log_grid;
+pub mod palette;
+pub mod ports;
+pub mod problems;
 pub mod project_detail;
 pub mod project_list;
 pub mod project_status;
+pub mod shell;
+pub mod timeline;
 
 pub trait UI {
   fn draw(state: &AppState, frame: &mut Frame, area: Rect);
 }
+
+/// Splits `text` into spans, styling the characters at the `matched` indices with `style`.
+pub fn highlight<'a>(text: &'a str, matched: &[usize], style: Style) -> Vec<Span<'a>> {
+  let span = |text: &'a str, is_match: bool| {
+    if is_match {
+      Span::styled(text, style)
+    } else {
+      Span::raw(text)
+    }
+  };
+  let mut spans = Vec::new();
+  let mut start = 0;
+  let mut in_match = false;
+  for (i, (byte, _)) in text.char_indices().enumerate() {
+    let is_match = matched.contains(&i);
+    if is_match != in_match && byte > start {
+      spans.push(span(&text[start..byte], in_match));
+      start = byte;
+    }
+    in_match = is_match;
+  }
+  spans.push(span(&text[start..], in_match));
+  spans
+}
@@ -0,0 +1,49 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Clear, Paragraph},
+};
+
+use crate::components::home::AppState;
+
+use super::UI;
+
+pub struct CommandLine;
+
+impl UI for CommandLine {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let command_line = &state.command_line;
+    let base = state.theme.style("base");
+    let input_area = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+    frame.render_widget(Clear, input_area);
+    frame.render_widget(
+      Paragraph::new(format!(":{}", command_line.input)).style(base),
+      input_area,
+    );
+    frame.set_cursor_position(Position::new(
+      input_area.x + 1 + command_line.input.chars().count() as u16,
+      input_area.y,
+    ));
+
+    // Show the candidates above the input while cycling through them.
+    if command_line.completions.len() < 2 || input_area.y == area.y {
+      return;
+    }
+    let completions_area = Rect::new(area.x, input_area.y - 1, area.width, 1);
+    let mut spans = Vec::new();
+    for (index, completion) in command_line.completions.iter().enumerate() {
+      let word = completion.rsplit(' ').next().unwrap_or_default();
+      let style = if command_line.completion == Some(index) {
+        state.theme.style("list.selected")
+      } else {
+        base
+      };
+      spans.push(Span::styled(word, style));
+      spans.push(Span::raw("  "));
+    }
+    frame.render_widget(Clear, completions_area);
+    frame.render_widget(
+      Paragraph::new(Line::from(spans)).style(base),
+      completions_area,
+    );
+  }
+}
@@ -0,0 +1,72 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, Clear, List, ListItem, Scrollbar, ScrollbarState},
+};
+
+use crate::components::home::AppState;
+
+use super::UI;
+
+pub struct Help;
+
+impl Help {
+  fn popup_area(area: Rect) -> Rect {
+    let [_, vertical, _] = Layout::vertical([
+      Constraint::Percentage(15),
+      Constraint::Percentage(70),
+      Constraint::Percentage(15),
+    ])
+    .areas(area);
+    let [_, popup, _] = Layout::horizontal([
+      Constraint::Percentage(20),
+      Constraint::Percentage(60),
+      Constraint::Percentage(20),
+    ])
+    .areas(vertical);
+    popup
+  }
+}
+
+impl UI for Help {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let help = &state.help;
+    let area = Self::popup_area(area);
+    let entries = help.filtered_entries();
+    let key_width = entries
+      .iter()
+      .map(|entry| entry.keys.chars().count())
+      .max()
+      .unwrap_or_default();
+
+    let items: Vec<ListItem> = entries
+      .iter()
+      .skip(help.scroll)
+      .map(|entry| {
+        ListItem::new(Line::from(vec![
+          Span::styled(
+            format!("{:<key_width$}  ", entry.keys),
+            state.theme.style("key"),
+          ),
+          Span::raw(entry.description.clone()),
+        ]))
+      })
+      .collect();
+
+    let list = List::new(items).block(
+      Block::default()
+        .title(help.title.clone())
+        .title_bottom(Line::from(format!("Filter: {}", help.query)))
+        .title_bottom(Line::from("↓↑ scroll, esc to close").right_aligned())
+        .borders(Borders::ALL)
+        .border_style(state.theme.style("popup.border"))
+        .style(state.theme.style("base")),
+    );
+
+    let scrollbar = Scrollbar::default().style(state.theme.style("scrollbar"));
+    let mut scrollbar_state = ScrollbarState::new(entries.len()).position(help.scroll);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(list, area);
+    frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+  }
+}
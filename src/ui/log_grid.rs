@@ -0,0 +1,78 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders},
+};
+
+use crate::{
+  components::home::{ActiveComponent, AppState},
+  theme,
+};
+
+use super::{project_status::ProjectStatus, UI};
+
+/// Pinned project logs tiled in up to two rows.
+pub struct LogGrid;
+
+impl LogGrid {
+  /// Areas of `count` panes: one row for up to two panes, otherwise two rows.
+  pub fn pane_areas(area: Rect, count: usize) -> Vec<Rect> {
+    if count == 0 {
+      return Vec::new();
+    }
+    let rows = if count <= 2 { 1 } else { 2 };
+    let per_row = count.div_ceil(rows);
+    Layout::vertical(vec![Constraint::Ratio(1, rows as u32); rows])
+      .split(area)
+      .iter()
+      .enumerate()
+      .flat_map(|(row, row_area)| {
+        let columns = per_row.min(count - row * per_row);
+        Layout::horizontal(vec![Constraint::Ratio(1, columns as u32); columns])
+          .split(*row_area)
+          .to_vec()
+      })
+      .collect()
+  }
+}
+
+impl UI for LogGrid {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let areas = Self::pane_areas(area, state.log_panes.len());
+    for (index, (pane, pane_area)) in state.log_panes.iter().zip(areas).enumerate() {
+      let project = &state.projects[pane.project];
+      let focused =
+        index == state.focused_pane && state.active_component == ActiveComponent::Status;
+      let border_style = if focused {
+        state.theme.style("border.active")
+      } else {
+        state.theme.style("border.inactive")
+      };
+      let follow = if pane.view.follow { " [follow]" } else { "" };
+      let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(Line::from(vec![
+          Span::styled(
+            format!(" {} ", project.name),
+            theme::project_style(&project.name).bold(),
+          ),
+          Span::raw(format!("{} {follow}", project.status)),
+        ]));
+      if let Some(pty) = state.interacting_pty(Some(pane.project)) {
+        let block = block.title_bottom(ProjectStatus::interact_hint(state));
+        ProjectStatus::draw_terminal(frame, pane_area, block, pty);
+        continue;
+      }
+      let output = project.output.lock().unwrap().clone();
+      ProjectStatus::draw_output(
+        state,
+        frame,
+        pane_area,
+        block,
+        &output,
+        &pane.view,
+        index == state.focused_pane,
+      );
+    }
+  }
+}
@@ -0,0 +1,82 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::components::home::AppState;
+
+use super::{highlight, UI};
+
+pub struct Palette;
+
+impl Palette {
+  fn popup_area(area: Rect) -> Rect {
+    let [_, vertical, _] = Layout::vertical([
+      Constraint::Percentage(10),
+      Constraint::Percentage(60),
+      Constraint::Percentage(30),
+    ])
+    .areas(area);
+    let [_, popup, _] = Layout::horizontal([
+      Constraint::Percentage(20),
+      Constraint::Percentage(60),
+      Constraint::Percentage(20),
+    ])
+    .areas(vertical);
+    popup
+  }
+}
+
+impl UI for Palette {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let palette = &state.palette;
+    let area = Self::popup_area(area);
+    let block = Block::default()
+      .title("Command Palette")
+      .title_bottom(Line::from("↓↑ select, enter to run, esc to close").right_aligned())
+      .borders(Borders::ALL)
+      .border_style(state.theme.style("popup.border"))
+      .style(state.theme.style("base"));
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let [input_area, list_area] =
+      Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+    let input = Paragraph::new(Line::from(vec![
+      Span::styled("> ", state.theme.style("key")),
+      Span::raw(palette.query.as_str()),
+    ]))
+    .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(input, input_area);
+    frame.set_cursor_position(Position::new(
+      input_area.x + 2 + palette.query.chars().count() as u16,
+      input_area.y,
+    ));
+
+    let width = list_area.width as usize;
+    let match_style = state.theme.style("list.match");
+    let items: Vec<ListItem> = palette
+      .matches()
+      .iter()
+      .map(|m| {
+        let entry = &palette.entries[m.index];
+        let mut spans = highlight(&entry.label, &m.indices, match_style);
+        if palette.query.is_empty() && palette.is_recent(entry) {
+          spans.push(Span::raw(" (recent)").dim());
+        }
+        if let Some(keys) = &entry.keys {
+          let used: usize = spans.iter().map(Span::width).sum();
+          let padding = width.saturating_sub(used + keys.chars().count());
+          spans.push(Span::raw(" ".repeat(padding)));
+          spans.push(Span::styled(keys.as_str(), state.theme.style("key")));
+        }
+        ListItem::new(Line::from(spans))
+      })
+      .collect();
+
+    let list = List::new(items).highlight_style(state.theme.style("list.selected"));
+    let mut list_state = ListState::default().with_selected(Some(palette.selected));
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+  }
+}
@@ -0,0 +1,77 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarState},
+};
+
+use crate::{
+  components::home::{ActiveComponent, AppState},
+  theme,
+};
+
+use super::UI;
+
+/// The listening TCP ports and the processes and projects owning them.
+pub struct PortsView;
+
+impl UI for PortsView {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let ports = &state.ports.ports;
+    let address_width = ports
+      .iter()
+      .map(|port| port.address.chars().count())
+      .max()
+      .unwrap_or_default();
+
+    let height = area.height.saturating_sub(2) as usize;
+    let start = state.ports.selected.saturating_sub(height / 2);
+    let items: Vec<ListItem> = ports
+      .iter()
+      .enumerate()
+      .skip(start)
+      .take(height)
+      .map(|(index, port)| {
+        let pid = port.pid.map(|pid| pid.to_string()).unwrap_or_default();
+        let command = port.command.as_deref().unwrap_or("?");
+        let mut spans = vec![
+          Span::styled(format!("{:>5} ", port.port), state.theme.style("key")),
+          Span::raw(format!("{:<address_width$} ", port.address)),
+          Span::raw(format!("{pid:>7} ")).dim(),
+          Span::raw(format!("{command} ")),
+        ];
+        if let Some(project) = &port.project {
+          spans.push(Span::styled(
+            project.as_str(),
+            theme::project_style(project),
+          ));
+        }
+        let item = ListItem::new(Line::from(spans));
+        if index == state.ports.selected {
+          item.style(state.theme.style("list.selected"))
+        } else {
+          item
+        }
+      })
+      .collect();
+
+    let border_style = if state.active_component == ActiveComponent::Status {
+      state.theme.style("border.active")
+    } else {
+      state.theme.style("border.inactive")
+    };
+    let list = List::new(items).block(
+      Block::default()
+        .title(format!("Listening ports: {}", ports.len()))
+        .title_bottom(
+          Line::from("j/k: select, x: kill, o: open, y: copy URL, P: close").right_aligned(),
+        )
+        .borders(Borders::ALL)
+        .border_style(border_style),
+    );
+
+    let scrollbar = Scrollbar::default().style(state.theme.style("scrollbar"));
+    let mut scrollbar_state = ScrollbarState::new(ports.len()).position(state.ports.selected);
+
+    frame.render_widget(list, area);
+    frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+  }
+}
@@ -0,0 +1,86 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarState},
+};
+
+use crate::{
+  components::home::{ActiveComponent, AppState},
+  problems::{ProblemCounts, ProblemSeverity},
+  theme,
+};
+
+use super::UI;
+
+/// Diagnostics of all projects, like the problems panel of an editor.
+pub struct ProblemsView;
+
+impl UI for ProblemsView {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let problems = state.problems.all();
+    let name_width = problems
+      .iter()
+      .map(|(project, _)| project.chars().count())
+      .max()
+      .unwrap_or_default();
+
+    let height = area.height.saturating_sub(2) as usize;
+    let start = state.problems.selected.saturating_sub(height / 2);
+    let items: Vec<ListItem> = problems
+      .iter()
+      .enumerate()
+      .skip(start)
+      .take(height)
+      .map(|(index, (project, problem))| {
+        let (icon, severity_style) = match problem.severity {
+          ProblemSeverity::Error => ("✖", state.theme.style("problem.error")),
+          ProblemSeverity::Warning => ("⚠", state.theme.style("problem.warning")),
+        };
+        let mut spans = vec![
+          Span::styled(
+            format!("{project:<name_width$} "),
+            theme::project_style(project),
+          ),
+          Span::styled(format!("{icon} "), severity_style),
+          Span::raw(format!(
+            "{}:{}:{} ",
+            problem.file, problem.line, problem.column
+          ))
+          .underlined(),
+          Span::raw(problem.message.as_str()),
+        ];
+        if let Some(rule) = &problem.rule {
+          spans.push(Span::raw(format!(" {rule}")).dim());
+        }
+        let item = ListItem::new(Line::from(spans));
+        if index == state.problems.selected {
+          item.style(state.theme.style("list.selected"))
+        } else {
+          item
+        }
+      })
+      .collect();
+
+    let counts = ProblemCounts::of(problems.iter().map(|(_, problem)| *problem));
+    let border_style = if state.active_component == ActiveComponent::Status {
+      state.theme.style("border.active")
+    } else {
+      state.theme.style("border.inactive")
+    };
+    let list = List::new(items).block(
+      Block::default()
+        .title(format!(
+          "Problems: {} errors, {} warnings",
+          counts.errors, counts.warnings
+        ))
+        .title_bottom(Line::from("j/k: select, o: open in editor, e: close").right_aligned())
+        .borders(Borders::ALL)
+        .border_style(border_style),
+    );
+
+    let scrollbar = Scrollbar::default().style(state.theme.style("scrollbar"));
+    let mut scrollbar_state = ScrollbarState::new(problems.len()).position(state.problems.selected);
+
+    frame.render_widget(list, area);
+    frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+  }
+}
@@ -3,31 +3,69 @@ use ratatui::{
   widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarState, Wrap},
 };
 
-use crate::components::home::AppState;
+use crate::{
+  action::{Action, ProjectCommand, ProjectScript},
+  components::home::AppState,
+};
 
 use super::UI;
 
+/// Hints in the bottom border as `(text, style key, action)`; clicking one runs its action.
+const HINTS: [(&str, &str, Action); 3] = [
+  (
+    "Start: s",
+    "hint.start",
+    Action::ProjectScript(ProjectScript::Start),
+  ),
+  (
+    "| Kill: c",
+    "hint.kill",
+    Action::ProjectCommand(ProjectCommand::Stop),
+  ),
+  (
+    "| Build: b",
+    "hint.build",
+    Action::ProjectScript(ProjectScript::Build),
+  ),
+];
+
 pub struct ProjectDetail;
 
 impl ProjectDetail {
-  fn build_block<'a>() -> Block<'a> {
-    let start = Line::from("Start: s").style(Style::default().fg(Color::Green).bold());
-    let kill = Line::from("| Kill: c").style(Style::default().fg(Color::Red).bold());
-    let build = Line::from("| Build: b").style(Style::default().fg(Color::Blue).bold());
-
-    Block::default()
-      .title("Details")
-      .borders(Borders::ALL)
-      .title_bottom(start)
-      .title_bottom(kill)
-      .title_bottom(build)
+  fn build_block<'a>(state: &AppState) -> Block<'a> {
+    HINTS.iter().fold(
+      Block::default().title("Details").borders(Borders::ALL),
+      |block, (text, style, _)| {
+        block.title_bottom(Line::from(*text).style(state.theme.style(style)))
+      },
+    )
+  }
+
+  /// The action of the hint drawn at `column` of the bottom border of `area`.
+  pub fn hint_at(area: Rect, column: u16) -> Option<Action> {
+    // Titles start after the corner and are separated by a space.
+    let mut x = area.x + 1;
+    for (text, _, action) in HINTS {
+      let width = text.chars().count() as u16;
+      if (x..x + width).contains(&column) {
+        return Some(action);
+      }
+      x += width + 1;
+    }
+    None
   }
 }
 
 impl UI for ProjectDetail {
   fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
-    let project = state.get_selected_project();
-    let details_text = serde_json::to_string_pretty(&project)
+    let mut details = serde_json::to_value(state.get_selected_project()).unwrap_or_default();
+    let usage = state
+      .selected_project()
+      .and_then(|index| state.usage_of(index));
+    if let (Some(details), Some(usage)) = (details.as_object_mut(), usage) {
+      details.insert("usage".to_string(), usage.summary().into());
+    }
+    let details_text = serde_json::to_string_pretty(&details)
       .unwrap_or_else(|_| "Error formatting project details".to_string());
     let lines: Vec<&str> = details_text.lines().collect();
     let visible_lines = lines
@@ -39,9 +77,9 @@ impl UI for ProjectDetail {
       .join("\n");
     let project_details = Paragraph::new(visible_lines)
       .block(Block::default().title("Details").borders(Borders::ALL))
-      .block(Self::build_block())
+      .block(Self::build_block(state))
       .wrap(Wrap { trim: false });
-    let scrollbar = Scrollbar::default().style(Style::default().fg(Color::Rgb(255, 97, 0)));
+    let scrollbar = Scrollbar::default().style(state.theme.style("scrollbar"));
     let mut scrollbar_state =
       ScrollbarState::new(details_text.lines().count()).position(state.detail_scroll);
 
@@ -1,4 +1,7 @@
-use crate::{components::home::Mode, project_manager};
+use crate::{
+  components::home::Mode, problems::ProblemCounts, project_manager, sampler::format_bytes,
+  search::ProjectSort,
+};
 use ratatui::{
   prelude::*,
   widgets::{Block, Borders, List, ListItem, Scrollbar, ScrollbarState},
@@ -8,15 +11,23 @@ use project_manager::ProjectStatus::Running;
 
 use crate::components::home::AppState;
 
-use super::UI;
+use super::{highlight, UI};
 
 pub struct ProjectList;
 
+impl ProjectList {
+  /// Index into `filtered_projects` of the first row shown in `area`, keeping the selection centred.
+  pub fn first_visible(state: &AppState, area: Rect) -> usize {
+    let list_height = area.height.saturating_sub(2) as usize;
+    state.selected_project_index.saturating_sub(list_height / 2)
+  }
+}
+
 impl UI for ProjectList {
   fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
-    let list_height = area.height as usize - 2;
+    let list_height = area.height.saturating_sub(2) as usize;
 
-    let start = state.selected_project_index.saturating_sub(list_height / 2);
+    let start = Self::first_visible(state, area);
     let end = (start + list_height).min(state.filtered_projects.len());
 
     let visible_projects = if start < state.filtered_projects.len() {
@@ -31,41 +42,73 @@ impl UI for ProjectList {
       .map(|(i, &project_index)| {
         let project = &state.projects[project_index];
         let global_index = start + i;
-        let text_color = if project.status == Running {
-          Color::Rgb(126, 193, 14)
+        let text_style = if project.status == Running {
+          state.theme.style("list.running")
         } else {
-          Color::White
+          state.theme.style("list.idle")
         };
-        let mut item = ListItem::new(format!(
-          "{}{}",
-          if project.status == Running {
-            "● "
-          } else {
-            "○ "
-          },
-          project.name
-        ))
-        .style(Style::default().fg(text_color));
+        let marker = if project.status == Running {
+          "● "
+        } else {
+          "○ "
+        };
+        let matched = state
+          .search_matches
+          .get(global_index)
+          .map(Vec::as_slice)
+          .unwrap_or_default();
+        let mut spans = vec![Span::raw(marker)];
+        spans.extend(highlight(
+          &project.name,
+          matched,
+          state.theme.style("list.match"),
+        ));
+        let counts = ProblemCounts::of(state.problems.of(&project.name));
+        if counts.errors > 0 {
+          spans.push(Span::styled(
+            format!(" ✖{}", counts.errors),
+            state.theme.style("problem.error"),
+          ));
+        }
+        if counts.warnings > 0 {
+          spans.push(Span::styled(
+            format!(" ⚠{}", counts.warnings),
+            state.theme.style("problem.warning"),
+          ));
+        }
+        if let Some(usage) = state.usage_of(project_index) {
+          spans.push(Span::styled(
+            format!(" {:.0}%", usage.cpu),
+            state.theme.style("usage.cpu"),
+          ));
+          spans.push(Span::styled(
+            format!(" {}", format_bytes(usage.memory)),
+            state.theme.style("usage.memory"),
+          ));
+        }
+        let mut item = ListItem::new(Line::from(spans)).style(text_style);
 
         if global_index == state.selected_project_index {
-          item = item.style(
-            Style::default()
-              .fg(Color::Rgb(0, 163, 225))
-              .bg(Color::LightYellow)
-              .add_modifier(Modifier::BOLD)
-              .add_modifier(Modifier::REVERSED),
-          );
+          item = item.style(state.theme.style("list.selected"));
         }
         item
       })
       .collect();
 
+    let title = match state.sort {
+      ProjectSort::None => "Frontend Projects".to_string(),
+      sort => format!("Frontend Projects by {sort}"),
+    };
     let project_list = List::new(project_items)
       .block(
         Block::default()
-          .title(Line::from("Frontend Projects").left_aligned())
+          .title(Line::from(title).left_aligned())
           .title_bottom(if state.mode == Mode::Search {
-            Line::from(format!("/ {}", state.search_query))
+            Line::from(format!(
+              "/ {} ({} matches)",
+              state.search_query,
+              state.filtered_projects.len()
+            ))
           } else {
             Line::raw("")
           })
@@ -79,7 +122,7 @@ impl UI for ProjectList {
       )
       .highlight_style(Style::default().add_modifier(Modifier::BOLD));
 
-    let scrollbar = Scrollbar::default().style(Style::default().fg(Color::Rgb(255, 97, 0)));
+    let scrollbar = Scrollbar::default().style(state.theme.style("scrollbar"));
     let mut scrollbar_state =
       ScrollbarState::new(state.filtered_projects.len()).position(state.selected_project_index);
 
@@ -1,40 +1,238 @@
 use super::UI;
-use crate::components::home::AppState;
+use crate::{
+  components::home::AppState,
+  config::key_event_to_string,
+  log_search::LogPrompt,
+  log_view::LogView,
+  pty::Pty,
+  sampler::{format_bytes, UsageHistory},
+};
+use std::time::Instant;
+
 use ratatui::{
   prelude::*,
-  widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarState, Wrap},
+  widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarState, Sparkline, Wrap},
 };
 
 pub struct ProjectStatus;
 
+/// "N new lines" shown while a log is not following its output.
+pub fn new_lines_indicator<'a>(state: &AppState, new_lines: usize) -> Option<Line<'a>> {
+  match new_lines {
+    0 => None,
+    1 => Some(Line::from("↓ 1 new line, G to follow")),
+    lines => Some(Line::from(format!("↓ {lines} new lines, G to follow"))),
+  }
+  .map(|line| line.style(state.theme.style("key")))
+}
+
+impl ProjectStatus {
+  /// Draws the filtered and highlighted `output` in `block`, scrolled as `view` says. The
+  /// `focused` view also shows the log search prompt.
+  pub fn draw_output(
+    state: &AppState,
+    frame: &mut Frame,
+    area: Rect,
+    mut block: Block,
+    output: &str,
+    view: &LogView,
+    focused: bool,
+  ) {
+    let search = &state.log_search;
+    let lines = search.visible_lines(output);
+    // Wrap the lines here rather than in the paragraph, so the view scrolls by drawn rows.
+    let rows = view.rows(&lines);
+    let log_scroll = view.first_line(rows.len());
+
+    if let Some(indicator) = new_lines_indicator(state, view.new_wrapped_lines(&rows)) {
+      block = block.title_bottom(indicator);
+    }
+    if focused {
+      if let Some(title) = Self::search_title(state, &lines) {
+        block = block.title_bottom(title);
+      }
+    }
+
+    // Highlight only the lines that can be drawn, not the whole output.
+    let match_style = state.theme.style("list.match");
+    let height = block.inner(area).height as usize;
+    let shown = &rows[log_scroll..(log_scroll + height).min(rows.len())];
+    let text: Vec<Line> = shown
+      .chunk_by(|a, b| a.line == b.line)
+      .flat_map(|line_rows| {
+        let ranges: Vec<_> = line_rows.iter().map(|row| row.start..row.end).collect();
+        let line = lines[line_rows[0].line];
+        search.highlight(line, &ranges, &state.highlights, match_style)
+      })
+      .collect();
+    let project_output = Paragraph::new(text).block(block);
+
+    let mut scrollbar_state = ScrollbarState::new(rows.len()).position(log_scroll);
+
+    let scrollbar = Scrollbar::default().style(state.theme.style("scrollbar"));
+
+    frame.render_widget(project_output, area);
+    frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+  }
+
+  /// The key that stops sending keys to a terminal, e.g. `<ctrl-g>`.
+  pub fn escape_key(state: &AppState) -> String {
+    state
+      .interact
+      .escape
+      .as_ref()
+      .map(|key| format!("<{}>", key_event_to_string(key)))
+      .unwrap_or_default()
+  }
+
+  /// Draws the screen of a terminal receiving keys, resizing it to fit `area`.
+  pub fn draw_terminal(frame: &mut Frame, area: Rect, block: Block, pty: &Pty) {
+    let inner = block.inner(area);
+    pty.resize(inner.height, inner.width);
+    let (lines, cursor) = pty.screen();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+    if let Some((row, column)) = cursor {
+      frame.set_cursor_position((inner.x + column, inner.y + row));
+    }
+  }
+
+  pub fn interact_hint<'a>(state: &AppState) -> Line<'a> {
+    Line::from(format!("Interacting, {} to stop", Self::escape_key(state)))
+      .style(state.theme.style("key"))
+  }
+
+  /// Height of the status block above the output, with a second line for the usage
+  /// sparklines of running projects.
+  pub fn header_height(state: &AppState) -> u16 {
+    let running = state
+      .get_selected_project()
+      .is_some_and(|project| state.usage.contains_key(&project.name));
+    if running {
+      4
+    } else {
+      3
+    }
+  }
+
+  /// Draws the recent CPU and memory use in `history` as two sparklines side by side.
+  fn draw_usage(state: &AppState, frame: &mut Frame, area: Rect, history: &UsageHistory) {
+    let usage = history.latest().unwrap_or_default();
+    let cpu_label = format!("cpu {:>4.0}% ", usage.cpu);
+    let memory_label = format!(" mem {:>5} ", format_bytes(usage.memory));
+    let [cpu_label_area, cpu_area, memory_label_area, memory_area] = Layout::horizontal([
+      Constraint::Length(cpu_label.len() as u16),
+      Constraint::Fill(1),
+      Constraint::Length(memory_label.len() as u16),
+      Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    // The newest samples that fit.
+    let recent = |data: Vec<u64>, area: Rect| {
+      let skip = data.len().saturating_sub(area.width as usize);
+      data[skip..].to_vec()
+    };
+    let cpu = recent(history.cpu(), cpu_area);
+    let memory = recent(history.memory(), memory_area);
+    let cpu_style = state.theme.style("usage.cpu");
+    let memory_style = state.theme.style("usage.memory");
+    frame.render_widget(Span::styled(cpu_label, cpu_style), cpu_label_area);
+    frame.render_widget(
+      // At least one core, so idle servers do not fill the sparkline.
+      Sparkline::default()
+        .data(&cpu)
+        .max(cpu.iter().copied().max().unwrap_or_default().max(100))
+        .style(cpu_style),
+      cpu_area,
+    );
+    frame.render_widget(Span::styled(memory_label, memory_style), memory_label_area);
+    frame.render_widget(
+      Sparkline::default().data(&memory).style(memory_style),
+      memory_area,
+    );
+  }
+
+  /// The prompt being typed, or the search match counter and active filter.
+  fn search_title<'a>(state: &AppState, lines: &[&str]) -> Option<Line<'a>> {
+    let search = &state.log_search;
+    if let Some(prompt) = search.prompt {
+      let prefix = match prompt {
+        LogPrompt::Search => "/",
+        LogPrompt::Filter => "&",
+      };
+      return Some(Line::from(format!("{prefix}{}", search.input)).style(state.theme.style("key")));
+    }
+    let mut parts = Vec::new();
+    if let Some(regex) = &search.search {
+      let matches = search.matching_lines(lines).len();
+      parts.push(match search.current {
+        Some(current) if matches > 0 => format!("/{regex} {}/{matches}", current + 1),
+        _ => format!("/{regex} {matches} matches"),
+      });
+    }
+    if let Some(filter) = &search.filter {
+      let invert = if search.invert_filter { "!" } else { "" };
+      parts.push(format!("&{invert}{filter}"));
+    }
+    (!parts.is_empty()).then(|| Line::from(parts.join("  ")).right_aligned())
+  }
+}
+
 impl UI for ProjectStatus {
   fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
     if let Some(project) = state.get_selected_project() {
-      let project_status = Paragraph::new(format!("Status: {}", project.status))
+      let mut status = format!("Status: {}", project.status);
+      if let Some(restart) = state
+        .selected_project()
+        .and_then(|index| state.restarts.get(&index))
+      {
+        if restart.restarts > 0 {
+          status.push_str(&format!(", restarts: {}", restart.restarts));
+        }
+        if let Some(exit) = &restart.last_exit {
+          status.push_str(&format!(", last exit: {}", exit.reason));
+        }
+        if let Some(at) = restart.restart_at {
+          let wait = at.saturating_duration_since(Instant::now());
+          status.push_str(&format!(", restarting in {}s", wait.as_secs_f32().ceil()));
+        }
+        if restart.restart_on_exit {
+          status.push_str(", restarting once stopped");
+        }
+      }
+      let project_status = Paragraph::new(status)
         .block(Block::default().title("Status").borders(Borders::ALL))
         .wrap(Wrap { trim: false });
+      let usage = state.usage.get(&project.name);
+      let header_height = Self::header_height(state);
 
       let output_block = Block::default().title("Output").borders(Borders::ALL);
       let output_content = project.output.lock().unwrap().clone();
-      let total_lines = output_content.lines().count();
-
-      let project_output = Paragraph::new(output_content)
-        .block(output_block)
-        .wrap(Wrap { trim: false })
-        .scroll((state.log_scroll as u16, 0)); // Add vertical scrolling
 
       let chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
+        .constraints([Constraint::Length(header_height), Constraint::Min(1)].as_ref())
         .split(area);
 
-      let mut scrollbar_state = ScrollbarState::new(total_lines).position(state.log_scroll);
-
-      let scrollbar = Scrollbar::default().style(Style::default().fg(Color::Rgb(255, 97, 0)));
-
       frame.render_widget(project_status, chunks[0]);
-      frame.render_widget(project_output, chunks[1]);
-      frame.render_stateful_widget(scrollbar, chunks[1], &mut scrollbar_state);
+      if let Some(history) = usage {
+        let inner = chunks[0].inner(Margin::new(1, 1));
+        let row = Rect::new(inner.x, inner.y + 1, inner.width, 1).intersection(inner);
+        Self::draw_usage(state, frame, row, history);
+      }
+      if let Some(pty) = state.interacting_pty(state.selected_project()) {
+        let block = output_block.title_bottom(Self::interact_hint(state));
+        return Self::draw_terminal(frame, chunks[1], block, pty);
+      }
+      Self::draw_output(
+        state,
+        frame,
+        chunks[1],
+        output_block,
+        &output_content,
+        &state.log_view,
+        true,
+      );
     } else {
       let no_project = Paragraph::new("No project selected.")
         .block(Block::default().title("Status").borders(Borders::ALL))
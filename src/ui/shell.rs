@@ -0,0 +1,45 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, Tabs},
+};
+
+use crate::components::home::AppState;
+
+use super::{project_status::ProjectStatus, UI};
+
+/// Shell tabs filling the screen in place of the dashboard.
+pub struct ShellView;
+
+impl UI for ShellView {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let shell = &state.shell;
+    let Some(tab) = shell.current() else {
+      return;
+    };
+    let [tabs_area, screen_area] =
+      Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).areas(area);
+
+    let titles = shell
+      .tabs
+      .iter()
+      .enumerate()
+      .map(|(index, tab)| format!("{} {}", index + 1, tab.project));
+    let tabs = Tabs::new(titles)
+      .select(shell.current)
+      .highlight_style(state.theme.style("list.selected"));
+    frame.render_widget(tabs, tabs_area);
+
+    let escape = ProjectStatus::escape_key(state);
+    let block = Block::default()
+      .borders(Borders::ALL)
+      .border_style(state.theme.style("border.active"))
+      .title(format!("Shell: {}", tab.project))
+      .title_bottom(
+        Line::from(format!(
+          "{escape}: dashboard, ctrl-pgup/ctrl-pgdn: switch tabs, exit: close tab"
+        ))
+        .right_aligned(),
+      );
+    ProjectStatus::draw_terminal(frame, screen_area, block, &tab.pty);
+  }
+}
@@ -0,0 +1,80 @@
+use ratatui::{
+  prelude::*,
+  widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarState},
+};
+
+use crate::{
+  components::home::{ActiveComponent, AppState},
+  theme,
+};
+
+use super::{project_status::new_lines_indicator, UI};
+
+/// Output of several projects interleaved in arrival order, prefixed like `concurrently`.
+pub struct TimelineView;
+
+impl UI for TimelineView {
+  fn draw(state: &AppState, frame: &mut Frame, area: Rect) {
+    let timeline = &state.timeline;
+    let height = area.height.saturating_sub(2) as usize;
+    let (len, scroll, text) = timeline.with_lines(|lines| {
+      let name_width = lines
+        .iter()
+        .map(|line| line.project.chars().count())
+        .max()
+        .unwrap_or_default();
+      let scroll = timeline.view.first_line(lines.len());
+      // Copy only the shown lines out of the shared timeline.
+      let text: Vec<Line> = lines
+        .iter()
+        .skip(scroll)
+        .take(height)
+        .map(|line| {
+          Line::from(vec![
+            Span::raw(format!("{:>7.1}s ", line.at.as_secs_f32())).dim(),
+            Span::styled(
+              format!("{:<name_width$} │ ", line.project),
+              theme::project_style(&line.project),
+            ),
+            Span::raw(line.text.clone()),
+          ])
+        })
+        .collect();
+      (lines.len(), scroll, text)
+    });
+
+    let projects = if timeline.projects.is_empty() {
+      "all projects".to_string()
+    } else {
+      timeline.projects.join(", ")
+    };
+    let mut title = format!("Timeline: {projects}");
+    if !timeline.filter.is_empty() {
+      title.push_str(&format!(", matching `{}`", timeline.filter));
+    }
+    if timeline.view.follow {
+      title.push_str(" [follow]");
+    }
+    let border_style = if state.active_component == ActiveComponent::Status {
+      state.theme.style("border.active")
+    } else {
+      state.theme.style("border.inactive")
+    };
+    let mut block = Block::default().title(title);
+    if let Some(indicator) = new_lines_indicator(state, timeline.view.new_lines(len)) {
+      block = block.title_bottom(indicator);
+    }
+    let paragraph = Paragraph::new(text).block(
+      block
+        .title_bottom(Line::from("m: add/remove selected project, t: close").right_aligned())
+        .borders(Borders::ALL)
+        .border_style(border_style),
+    );
+
+    let scrollbar = Scrollbar::default().style(state.theme.style("scrollbar"));
+    let mut scrollbar_state = ScrollbarState::new(len).position(scroll);
+
+    frame.render_widget(paragraph, area);
+    frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
+  }
+}
@@ -0,0 +1,369 @@
+use std::{
+  collections::{BTreeSet, HashMap},
+  fs,
+  path::{Path, PathBuf},
+  sync::mpsc::{self, Receiver, Sender},
+  time::{Duration, Instant},
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use portable_pty::ChildKiller;
+
+use crate::{
+  action::ProjectScript,
+  project_manager::{Project, PACKAGE_MANAGER},
+  pty::{Exit, Pty},
+};
+
+/// Paths the file watcher skips, e.g. `node_modules` or `*.tsbuildinfo`.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules(GlobSet);
+
+impl IgnoreRules {
+  /// Compiles `patterns`, skipping the invalid ones and returning their errors.
+  pub fn new(patterns: &[String]) -> (Self, Vec<String>) {
+    let mut builder = GlobSetBuilder::new();
+    let mut errors = Vec::new();
+    for pattern in patterns {
+      match Glob::new(pattern) {
+        Ok(glob) => {
+          builder.add(glob);
+        }
+        Err(err) => errors.push(format!("Invalid `watch_ignore` glob `{pattern}`: {err}")),
+      }
+    }
+    let set = builder.build().unwrap_or_else(|_| GlobSet::empty());
+    (Self(set), errors)
+  }
+
+  /// Whether a path relative to its project, or any of its components, matches a pattern.
+  pub fn is_ignored(&self, relative: &Path) -> bool {
+    self.0.is_match(relative)
+      || relative
+        .components()
+        .any(|component| self.0.is_match(component.as_os_str()))
+  }
+}
+
+/// Watches project directories for changed files.
+pub struct ProjectWatcher {
+  watcher: RecommendedWatcher,
+  events: Receiver<notify::Event>,
+  roots: Vec<PathBuf>,
+  ignore: IgnoreRules,
+}
+
+impl ProjectWatcher {
+  pub fn new(roots: Vec<PathBuf>, ignore: IgnoreRules) -> notify::Result<Self> {
+    let (tx, events) = mpsc::channel();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+      if let Ok(event) = event {
+        let _ = tx.send(event);
+      }
+    })?;
+    let mut project_watcher = Self {
+      watcher,
+      events,
+      roots: roots.clone(),
+      ignore,
+    };
+    for root in roots {
+      project_watcher.watch_tree(&root)?;
+    }
+    Ok(project_watcher)
+  }
+
+  /// Watches `dir` and the directories below it that are not ignored. inotify watches single
+  /// directories, so skipping `node_modules` keeps their number down.
+  fn watch_tree(&mut self, dir: &Path) -> notify::Result<()> {
+    if self.is_ignored(dir) {
+      return Ok(());
+    }
+    self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+      if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+        self.watch_tree(&entry.path())?;
+      }
+    }
+    Ok(())
+  }
+
+  fn is_ignored(&self, path: &Path) -> bool {
+    self
+      .roots
+      .iter()
+      .find_map(|root| path.strip_prefix(root).ok())
+      .is_some_and(|relative| self.ignore.is_ignored(relative))
+  }
+
+  /// Files changed since the last call that are not ignored. New directories are watched too.
+  pub fn changes(&mut self) -> Vec<PathBuf> {
+    let events: Vec<notify::Event> = self.events.try_iter().collect();
+    let mut changed = Vec::new();
+    for event in events {
+      if event.kind.is_access() {
+        continue;
+      }
+      for path in event.paths {
+        if self.is_ignored(&path) {
+          continue;
+        }
+        let is_dir = fs::symlink_metadata(&path).is_ok_and(|metadata| metadata.is_dir());
+        if event.kind.is_create() && is_dir {
+          if let Err(err) = self.watch_tree(&path) {
+            tracing::warn!("Not watching {}: {err}", path.display());
+          }
+        }
+        changed.push(path);
+      }
+    }
+    changed
+  }
+}
+
+/// A build started by file changes.
+pub struct Rebuild {
+  /// Index of the project being built.
+  pub project: usize,
+  name: String,
+  pty: Pty,
+  killer: Box<dyn ChildKiller + Send + Sync>,
+  tx: Sender<String>,
+}
+
+impl Rebuild {
+  /// Runs the build script of a project, returning the receiver of its output.
+  pub fn start(project: usize, name: &str, dir: &Path) -> Result<(Self, Receiver<String>), String> {
+    let script = ProjectScript::Build.to_string();
+    let (pty, child, reader) = Pty::spawn(PACKAGE_MANAGER, &[&script], dir)
+      .map_err(|err| format!("Failed to rebuild {name}: {err}"))?;
+    let killer = child.clone_killer();
+    let (tx, rx) = mpsc::channel();
+    let _ = tx.send(format!("Rebuilding {name} after file changes"));
+    let finished_name = name.to_string();
+    pty.clone().forward(child, reader, tx.clone(), move |exit| {
+      format!(
+        "Rebuild of {finished_name} finished with status: {}",
+        exit.reason
+      )
+    });
+    let rebuild = Self {
+      project,
+      name: name.to_string(),
+      pty,
+      killer,
+      tx,
+    };
+    Ok((rebuild, rx))
+  }
+
+  pub fn exit(&self) -> Option<Exit> {
+    self.pty.exit()
+  }
+
+  /// Stops the build, whose output newer changes made outdated.
+  pub fn cancel(&mut self) {
+    let _ = self.tx.send(format!(
+      "Cancelling the rebuild of {}, files changed",
+      self.name
+    ));
+    if let Err(err) = self.killer.kill() {
+      tracing::warn!("Failed to cancel the rebuild of {}: {err}", self.name);
+    }
+  }
+}
+
+/// Whether `project` lists `dependency` among its dependencies, plainly or scoped like
+/// `@acme/ui-kit`.
+fn depends_on(project: &Project, dependency: &Project) -> bool {
+  project
+    .dependencies
+    .iter()
+    .any(|name| name.rsplit('/').next() == Some(dependency.name.as_str()))
+}
+
+/// `changed` and the projects depending on them, directly or not, with dependencies before
+/// their dependents.
+pub fn build_order(projects: &[Project], changed: &[usize]) -> Vec<usize> {
+  let mut affected: BTreeSet<usize> = changed.iter().copied().collect();
+  let mut stack = changed.to_vec();
+  while let Some(index) = stack.pop() {
+    for (dependent, project) in projects.iter().enumerate() {
+      if depends_on(project, &projects[index]) && affected.insert(dependent) {
+        stack.push(dependent);
+      }
+    }
+  }
+
+  fn visit(
+    projects: &[Project],
+    affected: &BTreeSet<usize>,
+    index: usize,
+    visited: &mut BTreeSet<usize>,
+    order: &mut Vec<usize>,
+  ) {
+    if !visited.insert(index) {
+      return;
+    }
+    for &dependency in affected {
+      if depends_on(&projects[index], &projects[dependency]) {
+        visit(projects, affected, dependency, visited, order);
+      }
+    }
+    order.push(index);
+  }
+
+  let mut visited = BTreeSet::new();
+  let mut order = Vec::new();
+  for &index in &affected {
+    visit(projects, &affected, index, &mut visited, &mut order);
+  }
+  order
+}
+
+/// Projects waiting to be rebuilt, by index into the project list.
+#[derive(Debug, Default)]
+pub struct RebuildQueue {
+  /// When files of each project last changed, until they settle.
+  changed: HashMap<usize, Instant>,
+  /// Projects to build one after another, dependencies first.
+  queue: Vec<usize>,
+}
+
+impl RebuildQueue {
+  pub fn changed(&mut self, project: usize, now: Instant) {
+    self.changed.insert(project, now);
+  }
+
+  /// Queues the projects whose files stayed unchanged for `debounce`, and their dependents,
+  /// keeping those `buildable` says.
+  pub fn settle(
+    &mut self,
+    projects: &[Project],
+    debounce: Duration,
+    now: Instant,
+    buildable: impl Fn(usize) -> bool,
+  ) {
+    let settled: Vec<usize> = self
+      .changed
+      .iter()
+      .filter(|(_, at)| now.duration_since(**at) >= debounce)
+      .map(|(index, _)| *index)
+      .collect();
+    if settled.is_empty() {
+      return;
+    }
+    for index in &settled {
+      self.changed.remove(index);
+    }
+    let pending = [self.queue.as_slice(), &settled].concat();
+    self.queue = build_order(projects, &pending)
+      .into_iter()
+      .filter(|&index| buildable(index))
+      .collect();
+  }
+
+  /// The next project to build, once no files are changing.
+  pub fn next(&mut self) -> Option<usize> {
+    if !self.changed.is_empty() || self.queue.is_empty() {
+      return None;
+    }
+    Some(self.queue.remove(0))
+  }
+
+  /// Moves queued projects to their new indices, dropping the removed ones.
+  pub fn remap(&mut self, remap: &[Option<usize>]) {
+    let moved = |index: &usize| remap.get(*index).copied().flatten();
+    self.changed = self
+      .changed
+      .iter()
+      .filter_map(|(index, at)| Some((moved(index)?, *at)))
+      .collect();
+    self.queue = self.queue.iter().filter_map(moved).collect();
+  }
+
+  /// Drops the dependents of a project whose build failed from the queue.
+  pub fn drop_dependents(&mut self, projects: &[Project], project: usize) {
+    let affected = build_order(projects, &[project]);
+    self.queue.retain(|index| !affected.contains(index));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn projects() -> Vec<Project> {
+    [
+      ("docs", vec!["web-app"]),
+      ("ui-kit", vec![]),
+      ("web-app", vec!["@acme/ui-kit", "react"]),
+      ("api", vec![]),
+    ]
+    .into_iter()
+    .map(|(name, dependencies)| Project {
+      name: name.to_string(),
+      dependencies: dependencies.into_iter().map(str::to_string).collect(),
+      ..Default::default()
+    })
+    .collect()
+  }
+
+  #[test]
+  fn test_ignore_rules() {
+    let patterns = [
+      "node_modules",
+      "*.tsbuildinfo",
+      "src/generated/**",
+      "lib/**",
+      "[oops",
+    ];
+    let (rules, errors) = IgnoreRules::new(&patterns.map(str::to_string));
+    assert_eq!(errors.len(), 1);
+    assert!(rules.is_ignored(Path::new("node_modules/react/index.js")));
+    assert!(rules.is_ignored(Path::new("packages/a/node_modules")));
+    assert!(rules.is_ignored(Path::new("tsconfig.tsbuildinfo")));
+    assert!(rules.is_ignored(Path::new("src/generated/api.ts")));
+    assert!(!rules.is_ignored(Path::new("src/index.ts")));
+    assert!(rules.is_ignored(Path::new("lib/index.js")));
+    assert!(!rules.is_ignored(Path::new("src/lib/index.ts")));
+  }
+
+  #[test]
+  fn test_build_order() {
+    let projects = projects();
+    assert_eq!(build_order(&projects, &[1]), vec![1, 2, 0]);
+    assert_eq!(build_order(&projects, &[0, 3]), vec![0, 3]);
+  }
+
+  #[test]
+  fn test_rebuild_queue() {
+    let projects = projects();
+    let debounce = Duration::from_millis(300);
+    let now = Instant::now();
+    let mut queue = RebuildQueue::default();
+    queue.changed(1, now);
+    queue.settle(&projects, debounce, now, |_| true);
+    assert_eq!(queue.next(), None);
+
+    let later = now + debounce;
+    queue.settle(&projects, debounce, later, |index| index != 0);
+    assert_eq!(queue.next(), Some(1));
+    queue.changed(3, later);
+    assert_eq!(queue.next(), None);
+    queue.settle(&projects, debounce, later + debounce, |index| index != 0);
+    assert_eq!(queue.queue, vec![2, 3]);
+
+    queue.remap(&[Some(0), Some(1), Some(3), Some(2)]);
+    assert_eq!(queue.queue, vec![3, 2]);
+    queue.drop_dependents(&projects, 1);
+    assert_eq!(queue.next(), Some(3));
+    assert_eq!(queue.next(), None);
+
+    queue.changed(2, later);
+    queue.remap(&[Some(0), Some(1), None, Some(2)]);
+    queue.settle(&projects, debounce, later + debounce, |_| true);
+    assert_eq!(queue.next(), None);
+  }
+}
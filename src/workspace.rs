@@ -0,0 +1,132 @@
+use std::{
+  path::{Path, PathBuf},
+  sync::mpsc::{self, Receiver},
+};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::{
+  project_manager::{Project, ProjectStatus},
+  pty::Pty,
+};
+
+/// Watches the workspace directory and the project manifests, to notice added, removed or
+/// edited projects.
+pub struct WorkspaceWatcher {
+  _watcher: RecommendedWatcher,
+  events: Receiver<notify::Event>,
+  root: PathBuf,
+}
+
+impl WorkspaceWatcher {
+  /// Watches `root` and the directories of `projects`, without descending into them.
+  pub fn new(root: &Path, projects: &[PathBuf]) -> notify::Result<Self> {
+    let (tx, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+      if let Ok(event) = event {
+        let _ = tx.send(event);
+      }
+    })?;
+    watcher.watch(root, RecursiveMode::NonRecursive)?;
+    for dir in projects {
+      watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok(Self {
+      _watcher: watcher,
+      events,
+      root: root.to_path_buf(),
+    })
+  }
+
+  /// Whether project directories or manifests changed since the last call.
+  pub fn changed(&self) -> bool {
+    self
+      .events
+      .try_iter()
+      .filter(|event| !event.kind.is_access())
+      .filter(|event| {
+        event.paths.iter().any(|path| {
+          path.parent() == Some(self.root.as_path())
+            || path.file_name().is_some_and(|name| name == "package.json")
+        })
+      })
+      .count()
+      > 0
+  }
+}
+
+fn is_running(project: &Project) -> bool {
+  project.status == ProjectStatus::Running || project.pty.as_ref().is_some_and(Pty::is_running)
+}
+
+/// Merges rescanned projects into `projects`. Known projects keep their output and processes
+/// and take the new manifest, new ones are appended, and removed ones are dropped unless their
+/// script still runs. Returns the new index of each old project.
+pub fn merge(projects: &mut Vec<Project>, mut discovered: Vec<Project>) -> Vec<Option<usize>> {
+  let mut remap = Vec::with_capacity(projects.len());
+  let mut merged = Vec::with_capacity(discovered.len());
+  for mut project in projects.drain(..) {
+    match discovered
+      .iter()
+      .position(|found| found.name == project.name)
+    {
+      Some(position) => {
+        let found = discovered.remove(position);
+        project.path = found.path;
+        project.dependencies = found.dependencies;
+        project.commands = found.commands;
+        project.tags = found.tags;
+      }
+      None if !is_running(&project) => {
+        remap.push(None);
+        continue;
+      }
+      None => {}
+    }
+    remap.push(Some(merged.len()));
+    merged.push(project);
+  }
+  merged.extend(discovered);
+  *projects = merged;
+  remap
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn project(name: &str, commands: &[&str], status: ProjectStatus) -> Project {
+    Project {
+      name: name.to_string(),
+      commands: commands.iter().map(|c| c.to_string()).collect(),
+      status,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn test_merge() {
+    let mut projects = vec![
+      project("api", &["start"], ProjectStatus::Running),
+      project("docs", &["build"], ProjectStatus::Idle),
+      project("legacy", &["start"], ProjectStatus::Running),
+      project("web-app", &["start"], ProjectStatus::Stopped),
+    ];
+    projects[0].output.lock().unwrap().push_str("listening");
+    let discovered = vec![
+      project("web-app", &["start", "storybook"], ProjectStatus::Idle),
+      project("api", &["start", "test"], ProjectStatus::Idle),
+      project("ui-kit", &["build"], ProjectStatus::Idle),
+    ];
+
+    let remap = merge(&mut projects, discovered);
+    assert_eq!(remap, vec![Some(0), None, Some(1), Some(2)]);
+    let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["api", "legacy", "web-app", "ui-kit"]);
+    assert_eq!(projects[0].status, ProjectStatus::Running);
+    assert_eq!(projects[0].commands, vec!["start", "test"]);
+    assert_eq!(*projects[0].output.lock().unwrap(), "listening");
+    assert_eq!(projects[2].status, ProjectStatus::Stopped);
+    assert_eq!(projects[2].commands, vec!["start", "storybook"]);
+  }
+}